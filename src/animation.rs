@@ -0,0 +1,305 @@
+//! Animated transitions of div geometry and visibility, based on CSS transitions
+//!
+//! The pane state is updated to the target values immediately when an animation starts,
+//! the browser then interpolates the displayed values.
+//! Any other change to the geometry or visibility of the pane interrupts a running animation.
+
+use crate::pane::Pane;
+use crate::state;
+use crate::storage::PaneStorage;
+use crate::*;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+static NEXT_ANIMATION: AtomicU64 = AtomicU64::new(0);
+
+/// Timing function of an animation, see CSS `transition-timing-function`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    #[default]
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicBezier(f32, f32, f32, f32),
+}
+
+/// Visual effect used when showing or hiding a div
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Effect {
+    /// Fade opacity in or out
+    Fade,
+    /// Grow from or shrink to the center of the div
+    Zoom,
+    /// Fade and zoom at the same time
+    FadeZoom,
+}
+
+/// Describes how a div is shown or hidden with an animation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub effect: Effect,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+/// How an animation has ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnimationResult {
+    Completed,
+    /// Another change to the div has stopped the animation before it completed.
+    /// The div state reflects that change.
+    Interrupted,
+}
+
+/// A running animation of a pane
+#[derive(Debug)]
+pub(crate) struct ActiveAnimation {
+    id: u64,
+    /// Inline CSS values overwritten by the animation, restored when it ends
+    restore: Vec<(&'static str, String)>,
+    hide_at_end: bool,
+}
+
+impl ActiveAnimation {
+    /// The inline value a property had before the animation overwrote it, empty if it was not set
+    pub(crate) fn saved_css(&self, property: &str) -> Option<&str> {
+        self.restore
+            .iter()
+            .find(|(p, _)| *p == property)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Easing {
+    pub fn to_css(&self) -> String {
+        match self {
+            Easing::Linear => "linear".to_owned(),
+            Easing::Ease => "ease".to_owned(),
+            Easing::EaseIn => "ease-in".to_owned(),
+            Easing::EaseOut => "ease-out".to_owned(),
+            Easing::EaseInOut => "ease-in-out".to_owned(),
+            Easing::CubicBezier(a, b, c, d) => format!("cubic-bezier({}, {}, {}, {})", a, b, c, d),
+        }
+    }
+}
+
+impl Transition {
+    pub fn fade(duration: Duration) -> Self {
+        Transition {
+            effect: Effect::Fade,
+            duration,
+            easing: Easing::default(),
+        }
+    }
+    pub fn zoom(duration: Duration) -> Self {
+        Transition {
+            effect: Effect::Zoom,
+            duration,
+            easing: Easing::default(),
+        }
+    }
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// The CSS properties animated by the effect, with their value when the div is invisible
+    fn properties(&self) -> &'static [(&'static str, &'static str)] {
+        match self.effect {
+            Effect::Fade => &[("opacity", "0")],
+            Effect::Zoom => &[("transform", "scale(0)")],
+            Effect::FadeZoom => &[("opacity", "0"), ("transform", "scale(0)")],
+        }
+    }
+}
+
+impl<PS: PaneStorage, CS: ClassStorage> GlobalState<PS, CS> {
+    pub(crate) fn animate_pane(
+        &mut self,
+        p: &DivHandle,
+        target: Rect,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<u64, DivError> {
+        self.cancel_animation(p)?;
+        let timing = format!("{}ms {}", duration.as_millis(), easing.to_css());
+        let transition = ["left", "top", "width", "height"]
+            .iter()
+            .map(|property| format!("{} {}", property, timing))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let pane = self.nodes.get_mut(p)?;
+        let id = pane.start_animation(&[("transition", &transition)], false)?;
+        pane.set_geometry(target);
+        self.redraw_pane(p)?;
+        Ok(id)
+    }
+    pub(crate) fn hide_pane_animated(
+        &mut self,
+        p: &DivHandle,
+        transition: &Transition,
+    ) -> Result<u64, DivError> {
+        self.cancel_animation(p)?;
+        let pane = self.nodes.get_mut(p)?;
+        let css_transition = transition_css(transition);
+        let id = pane.start_animation(&[("transition", &css_transition)], true)?;
+        for (property, value) in transition.properties() {
+            pane.save_and_set_css(property, value)?;
+        }
+        Ok(id)
+    }
+    pub(crate) fn show_pane_animated(
+        &mut self,
+        p: &DivHandle,
+        transition: &Transition,
+    ) -> Result<u64, DivError> {
+        self.cancel_animation(p)?;
+        // Display the div in its invisible state first, the transition starts when the values are restored
+        let pane = self.nodes.get_mut(p)?;
+        let id = pane.start_animation(&[], false)?;
+        for (property, value) in transition.properties() {
+            pane.save_and_set_css(property, value)?;
+        }
+        self.set_displayed(p, true)?;
+        let pane = self.nodes.get_mut(p)?;
+        // Reading the layout forces the browser to apply the invisible state before the transition starts
+        pane.node().offset_width();
+        pane.save_and_set_css("transition", &transition_css(transition))?;
+        pane.restore_animated_css(transition.properties())?;
+        Ok(id)
+    }
+    /// Stops a running animation of the pane, if any, at its target state
+    pub(crate) fn cancel_animation(&mut self, p: &DivHandle) -> Result<(), DivError> {
+        let pane = self.nodes.get_mut(p)?;
+        if let Some(animation) = pane.take_animation() {
+            pane.restore_css(&animation.restore)?;
+        }
+        Ok(())
+    }
+    /// Called when the duration of an animation has elapsed. Returns false if the animation has been interrupted.
+    fn finish_animation(&mut self, p: &DivHandle, id: u64) -> Result<bool, DivError> {
+        let pane = self.nodes.get_mut(p)?;
+        if pane.animation_id() != Some(id) {
+            return Ok(false);
+        }
+        let animation = pane.take_animation().unwrap();
+        if animation.hide_at_end {
+            self.set_displayed(p, false)?;
+        }
+        self.nodes.get_mut(p)?.restore_css(&animation.restore)?;
+        Ok(true)
+    }
+}
+
+fn transition_css(transition: &Transition) -> String {
+    transition
+        .properties()
+        .iter()
+        .map(|(property, _)| {
+            format!(
+                "{} {}ms {}",
+                property,
+                transition.duration.as_millis(),
+                transition.easing.to_css()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Pane {
+    /// Registers a new animation and sets the given CSS properties, which are restored when the animation ends
+    fn start_animation(
+        &mut self,
+        css: &[(&'static str, &str)],
+        hide_at_end: bool,
+    ) -> Result<u64, DivError> {
+        let id = NEXT_ANIMATION.fetch_add(1, Ordering::Relaxed);
+        self.set_animation(Some(ActiveAnimation {
+            id,
+            restore: vec![],
+            hide_at_end,
+        }));
+        for (property, value) in css {
+            self.save_and_set_css(property, value)?;
+        }
+        Ok(id)
+    }
+    fn animation_id(&self) -> Option<u64> {
+        self.animation().map(|a| a.id)
+    }
+    fn save_and_set_css(&mut self, property: &'static str, value: &str) -> Result<(), DivError> {
+        let style = self.node().style();
+        let old = style.get_property_value(property)?;
+        if let Some(animation) = self.animation_mut() {
+            if !animation.restore.iter().any(|(p, _)| *p == property) {
+                animation.restore.push((property, old));
+            }
+        }
+        style.set_property(property, value)?;
+        Ok(())
+    }
+    /// Restores the saved values of some properties while the animation keeps running
+    fn restore_animated_css(
+        &mut self,
+        properties: &[(&'static str, &'static str)],
+    ) -> Result<(), DivError> {
+        if let Some(animation) = self.animation_mut() {
+            let (restore, keep): (Vec<_>, Vec<_>) = animation
+                .restore
+                .drain(..)
+                .partition(|(p, _)| properties.iter().any(|(q, _)| q == p));
+            animation.restore = keep;
+            self.restore_css(&restore)?;
+        }
+        Ok(())
+    }
+    fn restore_css(&self, values: &[(&'static str, String)]) -> Result<(), DivError> {
+        let style = self.node().style();
+        for (property, value) in values {
+            if value.is_empty() {
+                style.remove_property(property)?;
+            } else {
+                style.set_property(property, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns a future that resolves once the duration has elapsed and the animation has been finished
+pub(crate) fn animation_end(
+    div: DivHandle,
+    id: u64,
+    duration: Duration,
+) -> Result<impl Future<Output = AnimationResult>, DivError> {
+    let window = web_sys::window().ok_or(DivError::MissingWindow)?;
+    let mut result = Ok(());
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let on_timeout = Closure::once_into_js(move || {
+            let completed = state::exec_mut(|state| state.finish_animation(&div, id));
+            let _ = resolve.call1(
+                &JsValue::NULL,
+                &JsValue::from_bool(completed.unwrap_or(false)),
+            );
+        });
+        result = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                on_timeout.unchecked_ref(),
+                duration.as_millis() as i32,
+            )
+            .map(|_| ());
+    });
+    result?;
+    let future = wasm_bindgen_futures::JsFuture::from(promise);
+    Ok(async {
+        match future.await {
+            Ok(value) if value.as_bool() == Some(true) => AnimationResult::Completed,
+            _ => AnimationResult::Interrupted,
+        }
+    })
+}
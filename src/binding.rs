@@ -0,0 +1,89 @@
+//! Updates of individual nodes in the content of a div, instead of replacing the entire content
+//!
+//! Nodes are bound to a key with the attribute `data-div-bind="key"` in the HTML of a div.
+//! The bound nodes are looked up once when the content is set and kept in the pane.
+
+use crate::pane::Pane;
+use crate::state;
+use crate::*;
+use web_sys::Element;
+
+/// Refers to the nodes bound to a key in the content of a div, obtained with `DivHandle::bind`.
+///
+/// Changing a bound node leaves the rest of the content untouched, which keeps focus, selection and input state intact.
+/// If several nodes are bound to the same key, all of them are updated.
+/// # Example
+/// ```no_run
+/// let div = div::new(0, 0, 200, 50, r#"Score: <span data-div-bind="score">0</span>"#).unwrap();
+/// div.bind("score").set_text("42").unwrap();
+/// div.bind("score").set_attr("title", "New highscore").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Binding {
+    div: DivHandle,
+    key: String,
+}
+
+impl DivHandle {
+    /// Refers to the nodes with the attribute `data-div-bind` set to the key, not counting those inside of child divs.
+    /// Using the binding fails with `DivError::MissingBinding` if there are no such nodes.
+    pub fn bind(&self, key: &str) -> Binding {
+        Binding {
+            div: *self,
+            key: key.to_owned(),
+        }
+    }
+}
+
+impl Binding {
+    /// Replaces the content of the bound nodes with plain text, which is not interpreted as HTML.
+    /// Bindings in the old content are dropped.
+    pub fn set_text(&self, text: &str) -> Result<(), DivError> {
+        self.replace_content(|node| node.set_text_content(Some(text)))
+    }
+    /// Replaces the content of the bound nodes with HTML.
+    /// Bindings inside of the new content can be used afterwards, those in the old content are dropped.
+    pub fn set_html(&self, html: &str) -> Result<(), DivError> {
+        self.replace_content(|node| node.set_inner_html(html))
+    }
+    /// Sets an HTML attribute on the bound nodes
+    pub fn set_attr(&self, name: &str, value: &str) -> Result<(), DivError> {
+        for node in self.nodes()? {
+            node.set_attribute(name, value)?;
+        }
+        Ok(())
+    }
+    /// Removes an HTML attribute from the bound nodes
+    pub fn remove_attr(&self, name: &str) -> Result<(), DivError> {
+        for node in self.nodes()? {
+            node.remove_attribute(name)?;
+        }
+        Ok(())
+    }
+    /// The bound DOM nodes, in document order
+    pub fn nodes(&self) -> Result<Vec<Element>, DivError> {
+        state::exec(|state| self.bound_nodes(state.nodes.get(&self.div)?))
+    }
+    pub fn div(&self) -> DivHandle {
+        self.div
+    }
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Replaces the content of each bound node and updates what the pane knows about its content
+    fn replace_content(&self, replace: impl Fn(&Element)) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            let pane = state.nodes.get_mut(&self.div)?;
+            for node in self.bound_nodes(pane)? {
+                replace(&node);
+            }
+            pane.drop_replaced_listeners();
+            pane.collect_bindings()
+        })
+    }
+    fn bound_nodes(&self, pane: &Pane) -> Result<Vec<Element>, DivError> {
+        pane.bound_nodes(&self.key)
+            .map(<[Element]>::to_vec)
+            .ok_or_else(|| DivError::MissingBinding(self.key.clone()))
+    }
+}
@@ -0,0 +1,189 @@
+//! Defines a builder to create divs with all their attributes in a single call
+//!
+//! The builder is an alternative to the positional `div::new` and `div::new_styled` functions.
+
+use crate::state;
+use crate::*;
+
+/// Collects everything needed to create a new div and creates it when calling `build`.
+///
+/// Obtain a builder with `div::builder()`.
+/// All settings are optional, a div built without any settings is empty and has size 0x0 at position (0,0).
+/// # Example
+/// ```no_run
+/// let div = div::builder()
+///     .pos(100, 100)
+///     .size(500, 300)
+///     .html("Hello world")
+///     .class("my-class")
+///     .css("color", "red")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DivBuilder {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) w: u32,
+    pub(crate) h: u32,
+    pub(crate) html: String,
+    pub(crate) classes: Vec<String>,
+    pub(crate) css: Vec<(String, String)>,
+    pub(crate) id: Option<String>,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) z: Option<i32>,
+    pub(crate) hidden: bool,
+    pub(crate) parent: Option<DivHandle>,
+    pub(crate) group: Option<DivGroup>,
+    pub(crate) auto_size: (bool, bool),
+    pub(crate) min_size: Option<(u32, u32)>,
+    pub(crate) max_size: Option<(u32, u32)>,
+    pub(crate) scoped_css: Vec<String>,
+    pub(crate) hide_strategy: Option<HideStrategy>,
+    pub(crate) anchor: Anchor,
+    pub(crate) pivot: Option<(f32, f32)>,
+    pub(crate) shadow: Option<ShadowMode>,
+}
+
+/// Starts building a new div. See `DivBuilder` for all options.
+pub fn builder() -> DivBuilder {
+    DivBuilder::default()
+}
+
+impl DivBuilder {
+    /// Position of the div, relative to the global origin or to the parent div if one has been set.
+    pub fn pos(mut self, x: i32, y: i32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+    /// Size of the div, in the original scale when initializing.
+    pub fn size(mut self, w: u32, h: u32) -> Self {
+        self.w = w;
+        self.h = h;
+        self
+    }
+    /// Let the width of the div follow its content instead of using a fixed width.
+    pub fn auto_width(mut self) -> Self {
+        self.auto_size.0 = true;
+        self
+    }
+    /// Let the height of the div follow its content instead of using a fixed height.
+    pub fn auto_height(mut self) -> Self {
+        self.auto_size.1 = true;
+        self
+    }
+    /// Let width and height of the div follow its content.
+    pub fn auto_size(self) -> Self {
+        self.auto_width().auto_height()
+    }
+    /// Lower limit for the size of the div, in the original scale when initializing.
+    pub fn min_size(mut self, w: u32, h: u32) -> Self {
+        self.min_size = Some((w, h));
+        self
+    }
+    /// Upper limit for the size of the div, in the original scale when initializing.
+    pub fn max_size(mut self, w: u32, h: u32) -> Self {
+        self.max_size = Some((w, h));
+        self
+    }
+    /// HTML content of the div
+    pub fn html(mut self, html: impl Into<String>) -> Self {
+        self.html = html.into();
+        self
+    }
+    /// Add a CSS class to the div. Can be called several times to add multiple classes.
+    /// Classes used by div internally, like `div-rs-hidden-display`, make `build` fail with `DivError::ReservedClass`.
+    pub fn class(mut self, class: impl Into<String>) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+    /// Add several CSS classes to the div at once.
+    pub fn classes<I, S>(mut self, classes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.classes.extend(classes.into_iter().map(Into::into));
+        self
+    }
+    /// Add an inline CSS property to the div.
+    pub fn css(mut self, property: impl Into<String>, value: impl Into<String>) -> Self {
+        self.css.push((property.into(), value.into()));
+        self
+    }
+    /// Add all properties of a typed style as inline CSS of the div.
+    pub fn style(mut self, style: &Style) -> Self {
+        for (property, value) in style.properties() {
+            self = self.css(property, value);
+        }
+        self
+    }
+    /// Add a style-sheet that only applies inside the div. See `DivHandle::add_scoped_css`.
+    pub fn scoped_css(mut self, css: impl Into<String>) -> Self {
+        self.scoped_css.push(css.into());
+        self
+    }
+    /// Set the HTML id attribute of the div.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+    /// Set an arbitrary HTML attribute on the div.
+    pub fn attr(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push((name.into(), value.into()));
+        self
+    }
+    /// Set the z-index of the div. By default, all divs have a z-index of 1.
+    pub fn z(mut self, z: i32) -> Self {
+        self.z = Some(z);
+        self
+    }
+    /// Create the div hidden. Call `show` on the handle to display it.
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+    /// Define how the div is hidden, overriding the global strategy.
+    pub fn hide_strategy(mut self, strategy: HideStrategy) -> Self {
+        self.hide_strategy = Some(strategy);
+        self
+    }
+    /// Create the div as a child of another div.
+    /// The position of the new div is then relative to the parent div, and it is deleted together with the parent div.
+    pub fn parent(mut self, parent: DivHandle) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+    /// Add the div to a group, see `DivGroup`.
+    pub fn group(mut self, group: DivGroup) -> Self {
+        self.group = Some(group);
+        self
+    }
+    /// Measure the position from an anchor point on the frame (or on the parent div) instead of the top-left corner.
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+    /// Define which point of the div is placed at its position, as fractions of its size.
+    /// For example, (0.5, 0.5) centers the div on its position.
+    /// By default, the pivot is the same as the anchor.
+    pub fn pivot(mut self, x: f32, y: f32) -> Self {
+        self.pivot = Some((x, y));
+        self
+    }
+    /// Attach a shadow root to the div and render its content into it.
+    ///
+    /// This shields the content from style-sheets of the surrounding page, which is useful when div is
+    /// mounted inside an existing site. Scoped CSS and child divs are placed in the shadow root as well,
+    /// the div-rs stylesheet that positions and hides child divs is adopted by the shadow root.
+    pub fn shadow(mut self, mode: ShadowMode) -> Self {
+        self.shadow = Some(mode);
+        self
+    }
+    /// Creates the div as configured.
+    /// Use the returned DivHandle to manipulate the div.
+    pub fn build(&self) -> Result<DivHandle, DivError> {
+        state::exec_mut(|state| state.new_pane(self))
+    }
+}
@@ -0,0 +1,80 @@
+/// A 2D camera that projects world coordinates to the frame of a `DivGroup`.
+///
+/// Divs added to a group with a camera are positioned in world coordinates.
+/// Whenever the camera changes, all divs of the group are re-projected.
+/// The projected point of the camera position is the group offset, hence use
+/// `DivGroup::set_offset` to define where in the frame the camera is looking at.
+/// # Example
+/// ```no_run
+/// use div::Camera;
+/// let labels = div::new_group().unwrap();
+/// // Put the camera center into the middle of a 1280x720 frame
+/// labels.set_offset(640, 360).unwrap();
+/// labels.add(div::new(2000, 1500, 100, 20, "Castle").unwrap()).unwrap();
+/// labels
+///     .set_camera(Some(Camera::new(1950.0, 1480.0).with_zoom(2.0).with_culling(true)))
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// World coordinates projected to the group offset
+    pub x: f32,
+    pub y: f32,
+    /// Uniform scaling of world coordinates and div sizes
+    pub zoom: f32,
+    /// Rotation of the camera in radians.
+    /// Only positions are rotated, the divs themselves stay upright.
+    pub rotation: f32,
+    /// Hide divs that are entirely outside of the frame
+    pub culling: bool,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            x: 0.0,
+            y: 0.0,
+            zoom: 1.0,
+            rotation: 0.0,
+            culling: false,
+        }
+    }
+}
+
+impl Camera {
+    /// A camera looking at the given world coordinates, without zoom or rotation
+    pub fn new(x: f32, y: f32) -> Self {
+        Camera {
+            x,
+            y,
+            ..Default::default()
+        }
+    }
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+    pub fn with_culling(mut self, culling: bool) -> Self {
+        self.culling = culling;
+        self
+    }
+    /// Projects world coordinates to coordinates relative to the group offset
+    pub fn project(&self, x: f32, y: f32) -> (f32, f32) {
+        let (dx, dy) = (x - self.x, y - self.y);
+        let (sin, cos) = (-self.rotation).sin_cos();
+        (
+            self.zoom * (dx * cos - dy * sin),
+            self.zoom * (dx * sin + dy * cos),
+        )
+    }
+    /// Inverse of `project`, converts coordinates relative to the group offset to world coordinates
+    pub fn unproject(&self, x: f32, y: f32) -> (f32, f32) {
+        let (x, y) = (x / self.zoom, y / self.zoom);
+        let (sin, cos) = self.rotation.sin_cos();
+        (self.x + x * cos - y * sin, self.y + x * sin + y * cos)
+    }
+}
@@ -0,0 +1,379 @@
+//! Typed inline styles, as an alternative to CSS property and value strings
+//!
+//! A `Style` collects CSS properties set through typed methods, so that misspelled property names
+//! and malformed values are caught by the compiler. Properties without a typed method can still be set with `Style::raw`.
+//!
+//! Position and size are controlled by div itself, hence there are no methods for `left`, `top`, `width` and `height`.
+
+use std::fmt;
+
+/// A set of inline CSS properties, applied with `DivHandle::set_style`, `DivBuilder::style` or `div::new_styled`.
+///
+/// Setting the same property twice keeps only the last value.
+/// # Example
+/// ```no_run
+/// use div::{Border, Color, Display, Length, Style};
+/// let style = Style::new()
+///     .display(Display::Flex)
+///     .background(Color::hex(0x202020))
+///     .color(Color::WHITE)
+///     .padding(Length::Px(8.0))
+///     .border(Border::solid(Length::Px(1.0), Color::rgb(200, 0, 0)))
+///     .opacity(0.9);
+/// let div = div::new_styled(0, 0, 300, 100, "Hello", &["box"], &style).unwrap();
+/// div.set_style(&Style::new().opacity(1.0)).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    properties: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Rgb(u8, u8, u8),
+    /// Red, green, blue and an alpha value between 0.0 and 1.0
+    Rgba(u8, u8, u8, f32),
+    Transparent,
+    CurrentColor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f32),
+    Percent(f32),
+    Em(f32),
+    Rem(f32),
+    /// Percent of the viewport width
+    Vw(f32),
+    /// Percent of the viewport height
+    Vh(f32),
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Display {
+    None,
+    Block,
+    Inline,
+    InlineBlock,
+    Flex,
+    InlineFlex,
+    Grid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlexDirection {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlexWrap {
+    NoWrap,
+    Wrap,
+    WrapReverse,
+}
+
+/// Distribution of items along the main axis of a flex or grid container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JustifyContent {
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+/// Alignment of items along the cross axis of a flex or grid container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlignItems {
+    Start,
+    End,
+    Center,
+    Stretch,
+    Baseline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BorderStyle {
+    None,
+    Solid,
+    Dashed,
+    Dotted,
+    Double,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Border {
+    pub width: Length,
+    pub style: BorderStyle,
+    pub color: Color,
+}
+
+/// A single transform function. Several of them are combined with `Style::transform`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    Translate(Length, Length),
+    Scale(f32, f32),
+    /// Clockwise rotation in degrees
+    Rotate(f32),
+    /// Skew along the x and y axis in degrees
+    Skew(f32, f32),
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets an arbitrary property, for everything not covered by the typed methods
+    pub fn raw(self, property: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set(property.into(), value.into())
+    }
+    pub fn color(self, color: Color) -> Self {
+        self.with("color", color)
+    }
+    pub fn background(self, color: Color) -> Self {
+        self.with("background-color", color)
+    }
+    pub fn display(self, display: Display) -> Self {
+        self.with("display", display)
+    }
+    pub fn flex_direction(self, direction: FlexDirection) -> Self {
+        self.with("flex-direction", direction)
+    }
+    pub fn flex_wrap(self, wrap: FlexWrap) -> Self {
+        self.with("flex-wrap", wrap)
+    }
+    /// How much the div grows and shrinks as an item of a flex container, relative to its siblings
+    pub fn flex(self, grow: f32, shrink: f32, basis: Length) -> Self {
+        self.with("flex", format!("{} {} {}", grow, shrink, basis))
+    }
+    pub fn justify_content(self, justify: JustifyContent) -> Self {
+        self.with("justify-content", justify)
+    }
+    pub fn align_items(self, align: AlignItems) -> Self {
+        self.with("align-items", align)
+    }
+    /// Space between items of a flex or grid container
+    pub fn gap(self, gap: Length) -> Self {
+        self.with("gap", gap)
+    }
+    pub fn padding(self, padding: Length) -> Self {
+        self.with("padding", padding)
+    }
+    pub fn margin(self, margin: Length) -> Self {
+        self.with("margin", margin)
+    }
+    pub fn border(self, border: Border) -> Self {
+        self.with("border", border)
+    }
+    pub fn border_radius(self, radius: Length) -> Self {
+        self.with("border-radius", radius)
+    }
+    pub fn font_size(self, size: Length) -> Self {
+        self.with("font-size", size)
+    }
+    /// Opacity between 0.0 and 1.0, values outside are clamped
+    pub fn opacity(self, opacity: f32) -> Self {
+        self.with("opacity", opacity.clamp(0.0, 1.0))
+    }
+    /// Sets the transform functions, applied from left to right. An empty list removes all transformations.
+    pub fn transform(self, transforms: &[Transform]) -> Self {
+        let value = if transforms.is_empty() {
+            "none".to_owned()
+        } else {
+            transforms
+                .iter()
+                .map(Transform::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        self.with("transform", value)
+    }
+    /// The properties and their values as CSS strings, in the order they have been set first
+    pub fn properties(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .map(|(property, value)| (property.as_str(), value.as_str()))
+    }
+    fn with(self, property: &str, value: impl fmt::Display) -> Self {
+        self.set(property.to_owned(), value.to_string())
+    }
+    fn set(mut self, property: String, value: String) -> Self {
+        match self.properties.iter_mut().find(|(p, _)| *p == property) {
+            Some(entry) => entry.1 = value,
+            None => self.properties.push((property, value)),
+        }
+        self
+    }
+}
+
+/// Allows using a `&Style` wherever CSS property and value pairs are expected, e.g. in `div::new_styled`
+impl<'a> IntoIterator for &'a Style {
+    type Item = &'a (String, String);
+    type IntoIter = std::slice::Iter<'a, (String, String)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.properties.iter()
+    }
+}
+
+/// Renders the style as the content of an HTML style attribute
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let css = self
+            .properties
+            .iter()
+            .map(|(property, value)| format!("{}: {};", property, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(f, "{}", css)
+    }
+}
+
+impl Color {
+    pub const BLACK: Color = Color::Rgb(0, 0, 0);
+    pub const WHITE: Color = Color::Rgb(255, 255, 255);
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color::Rgb(r, g, b)
+    }
+    pub fn rgba(r: u8, g: u8, b: u8, a: f32) -> Self {
+        Color::Rgba(r, g, b, a.clamp(0.0, 1.0))
+    }
+    /// Color from a hexadecimal RGB value, e.g. `0xff8800`
+    pub fn hex(rgb: u32) -> Self {
+        Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Color::Rgb(r, g, b) => write!(f, "rgb({}, {}, {})", r, g, b),
+            Color::Rgba(r, g, b, a) => write!(f, "rgba({}, {}, {}, {})", r, g, b, a),
+            Color::Transparent => write!(f, "transparent"),
+            Color::CurrentColor => write!(f, "currentcolor"),
+        }
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Length::Px(v) => write!(f, "{}px", v),
+            Length::Percent(v) => write!(f, "{}%", v),
+            Length::Em(v) => write!(f, "{}em", v),
+            Length::Rem(v) => write!(f, "{}rem", v),
+            Length::Vw(v) => write!(f, "{}vw", v),
+            Length::Vh(v) => write!(f, "{}vh", v),
+            Length::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+impl fmt::Display for Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Display::None => "none",
+            Display::Block => "block",
+            Display::Inline => "inline",
+            Display::InlineBlock => "inline-block",
+            Display::Flex => "flex",
+            Display::InlineFlex => "inline-flex",
+            Display::Grid => "grid",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Display for FlexDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            FlexDirection::Row => "row",
+            FlexDirection::RowReverse => "row-reverse",
+            FlexDirection::Column => "column",
+            FlexDirection::ColumnReverse => "column-reverse",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Display for FlexWrap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            FlexWrap::NoWrap => "nowrap",
+            FlexWrap::Wrap => "wrap",
+            FlexWrap::WrapReverse => "wrap-reverse",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Display for JustifyContent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            JustifyContent::Start => "flex-start",
+            JustifyContent::End => "flex-end",
+            JustifyContent::Center => "center",
+            JustifyContent::SpaceBetween => "space-between",
+            JustifyContent::SpaceAround => "space-around",
+            JustifyContent::SpaceEvenly => "space-evenly",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Display for AlignItems {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            AlignItems::Start => "flex-start",
+            AlignItems::End => "flex-end",
+            AlignItems::Center => "center",
+            AlignItems::Stretch => "stretch",
+            AlignItems::Baseline => "baseline",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Display for BorderStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            BorderStyle::None => "none",
+            BorderStyle::Solid => "solid",
+            BorderStyle::Dashed => "dashed",
+            BorderStyle::Dotted => "dotted",
+            BorderStyle::Double => "double",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Border {
+    pub fn solid(width: Length, color: Color) -> Self {
+        Border {
+            width,
+            style: BorderStyle::Solid,
+            color,
+        }
+    }
+}
+
+impl fmt::Display for Border {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.width, self.style, self.color)
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transform::Translate(x, y) => write!(f, "translate({}, {})", x, y),
+            Transform::Scale(x, y) => write!(f, "scale({}, {})", x, y),
+            Transform::Rotate(deg) => write!(f, "rotate({}deg)", deg),
+            Transform::Skew(x, y) => write!(f, "skew({}deg, {}deg)", x, y),
+        }
+    }
+}
@@ -2,41 +2,106 @@
 //!
 //! Almost the entire library interface is defined in this module.
 
+use crate::animation::animation_end;
 use crate::state;
 use crate::*;
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
 use web_sys::{HtmlElement, Node};
 
 /// A light-weight key to refer to the state necessary to manipulate a div.
 ///
 /// This is a unique identifier that will become invalid once the div has been deleted.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DivHandle(pub(crate) usize);
 
 impl DivHandle {
     /// Hides the div and all child nodes.
     ///
-    /// The div node is removed from the DOM but it is kept in memory.
+    /// By default, the div node is removed from the DOM but it is kept in memory.
     /// Call `delete` to give up memory or call `show` later to display pane again.
+    /// See `set_hide_strategy` for alternatives to removing the node.
     pub fn hide(&self) -> Result<(), DivError> {
-        state::exec_mut(|state| state.hide_pane(&self))
+        state::exec_mut(|state| state.hide_pane(self))
+    }
+    /// Define how this div is hidden, overriding the global strategy set with `div::set_hide_strategy`.
+    /// Pass None to use the global strategy again.
+    pub fn set_hide_strategy(&self, strategy: Option<HideStrategy>) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.nodes.get_mut(self)?.set_hide_strategy(strategy);
+            state.refresh_visibility(self)
+        })
     }
     /// Displays a div again after it has been hidden by calling `hide`
     pub fn show(&self) -> Result<(), DivError> {
-        state::exec_mut(|state| state.show_pane(&self))
+        state::exec_mut(|state| state.show_pane(self))
+    }
+    /// Hides the div after playing a transition, e.g. fading it out.
+    ///
+    /// The returned future resolves when the div has been hidden, or when the transition has been interrupted
+    /// by another change to the div, e.g. by calling `show`. The div is hidden even if the future is never polled.
+    pub fn hide_animated(
+        &self,
+        transition: Transition,
+    ) -> Result<impl Future<Output = AnimationResult>, DivError> {
+        let id = state::exec_mut(|state| state.hide_pane_animated(self, &transition))?;
+        animation_end(*self, id, transition.duration)
+    }
+    /// Displays a hidden div with a transition, e.g. fading it in.
+    ///
+    /// The returned future resolves when the transition has completed or has been interrupted.
+    pub fn show_animated(
+        &self,
+        transition: Transition,
+    ) -> Result<impl Future<Output = AnimationResult>, DivError> {
+        let id = state::exec_mut(|state| state.show_pane_animated(self, &transition))?;
+        animation_end(*self, id, transition.duration)
+    }
+    /// Moves and resizes the div smoothly over the given duration.
+    ///
+    /// The position and size reported by the DivHandle are immediately set to the target values.
+    /// Any other change to the geometry of the div interrupts the animation and the div jumps to the new values.
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # let div = div::new(0, 0, 100, 100, "").unwrap();
+    /// let future = div
+    ///     .animate_to(200, 0, 100, 100, Duration::from_millis(300), div::Easing::EaseOut)
+    ///     .unwrap();
+    /// wasm_bindgen_futures::spawn_local(async {
+    ///     future.await;
+    /// });
+    /// ```
+    pub fn animate_to(
+        &self,
+        x: i32,
+        y: i32,
+        w: u32,
+        h: u32,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<impl Future<Output = AnimationResult>, DivError> {
+        let target = Rect::new(x, y, w, h);
+        let id = state::exec_mut(|state| state.animate_pane(self, target, duration, easing))?;
+        animation_end(*self, id, duration)
     }
     /// Adjust the relative position of the div.
     ///
     /// The provided parameters are taken in the original scale when initializing,
     /// taking any calls to the global div::resize() into consideration.
     pub fn reposition(&self, x: i32, y: i32) -> Result<(), DivError> {
-        state::exec_mut(|state| state.update_pane(&self, Some(x), Some(y), None, None))
+        state::exec_mut(|state| state.update_pane(self, Some(x), Some(y), None, None))
     }
     /// Adjust the size of the div.
     ///
     /// The provided parameters are taken in the original scale when initializing,
     /// taking any calls to the global div::resize() into consideration.
     pub fn resize(&self, w: u32, h: u32) -> Result<(), DivError> {
-        state::exec_mut(|state| state.update_pane(&self, None, None, Some(w), Some(h)))
+        state::exec_mut(|state| state.update_pane(self, None, None, Some(w), Some(h)))
     }
     /// Adjust the position and size of the div in a single call, which is slightly more efficient than calling
     /// resize and reposition separately.
@@ -44,12 +109,167 @@ impl DivHandle {
     /// The provided parameters are taken in the original scale when initializing,
     /// taking any calls to the global div::resize() into consideration.
     pub fn reposition_and_resize(&self, x: i32, y: i32, w: u32, h: u32) -> Result<(), DivError> {
-        state::exec_mut(|state| state.update_pane(&self, Some(x), Some(y), Some(w), Some(h)))
+        state::exec_mut(|state| state.update_pane(self, Some(x), Some(y), Some(w), Some(h)))
+    }
+    /// Let width and / or height of the div follow its content.
+    /// A dimension that is not auto-sized uses the size set with `resize`.
+    pub fn set_auto_size(&self, auto_width: bool, auto_height: bool) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state
+                .nodes
+                .get_mut(self)?
+                .set_auto_size((auto_width, auto_height));
+            state.redraw_pane(self)
+        })
+    }
+    /// Limit the size of the div, in the original scale when initializing.
+    /// Most useful in combination with auto-sizing.
+    pub fn set_size_limits(
+        &self,
+        min_size: Option<(u32, u32)>,
+        max_size: Option<(u32, u32)>,
+    ) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state
+                .nodes
+                .get_mut(self)?
+                .set_size_limits(min_size, max_size);
+            state.redraw_pane(self)
+        })
+    }
+    /// The size of the div as rendered by the browser, converted to the original scale when initializing.
+    ///
+    /// In contrast to `size`, this also works for auto-sized divs.
+    /// Hidden divs are not rendered and have a measured size of 0x0.
+    pub fn measured_size(&self) -> Result<(u32, u32), DivError> {
+        state::exec(|state| state.measured_size(self))
+    }
+    /// Measure the position of the div from an anchor point on the frame (or on the parent div).
+    /// See `Anchor` for details.
+    pub fn set_anchor(&self, anchor: Anchor) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.nodes.get_mut(self)?.set_anchor(anchor);
+            state.redraw_pane(self)
+        })
+    }
+    /// The anchor the position of the div is measured from
+    pub fn anchor(&self) -> Result<Anchor, DivError> {
+        state::exec(|state| Ok(state.nodes.get(self)?.anchor()))
+    }
+    /// Define which point of the div is placed at its position, as fractions of its size.
+    /// Pass None to use the same point as the anchor, which is the default.
+    pub fn set_pivot(&self, pivot: Option<(f32, f32)>) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.nodes.get_mut(self)?.set_pivot(pivot);
+            state.redraw_pane(self)
+        })
+    }
+    /// The point of the div placed at its position, as fractions of its size
+    pub fn pivot(&self) -> Result<(f32, f32), DivError> {
+        state::exec(|state| Ok(state.nodes.get(self)?.pivot()))
+    }
+    /// The relative position of the div, in the original scale when initializing.
+    pub fn position(&self) -> Result<(i32, i32), DivError> {
+        self.rect().map(|r| (r.x, r.y))
+    }
+    /// The size of the div, in the original scale when initializing.
+    /// For auto-sized divs, use `measured_size` instead.
+    pub fn size(&self) -> Result<(u32, u32), DivError> {
+        self.rect().map(|r| (r.w, r.h))
+    }
+    /// Position and size of the div in the original scale when initializing, as passed to `reposition` and `resize`.
+    pub fn rect(&self) -> Result<Rect, DivError> {
+        state::exec(|state| Ok(state.nodes.get(self)?.rect()))
+    }
+    /// Position and size of the div in pixels as displayed, relative to the root element.
+    ///
+    /// This takes the global origin and the zoom factor from `div::resize` into account.
+    pub fn screen_rect(&self) -> Result<Rect, DivError> {
+        state::exec(|state| state.screen_rect(self))
+    }
+    /// Checks whether the div is currently displayed, i.e. neither the div nor its group have been hidden.
+    pub fn is_visible(&self) -> Result<bool, DivError> {
+        state::exec(|state| Ok(state.nodes.get(self)?.is_shown()))
+    }
+    /// Set the z-index of the div, which defines which divs are drawn on top of others.
+    /// By default, all divs have a z-index of 1.
+    pub fn set_z(&self, z: i32) -> Result<(), DivError> {
+        state::exec_mut(|state| state.nodes.get_mut(self)?.set_z(z))
+    }
+    /// Let the user move the div with a pointer (mouse, touch or pen).
+    ///
+    /// With a handle selector, dragging only starts on elements inside the div that match the CSS selector, e.g. a title bar.
+    /// With bounds, the div is kept inside the given rectangle, in the original scale when initializing.
+    /// The bounds are measured from the top-left corner of the frame (or of the parent div), whatever the anchor of the div is.
+    /// Use `on_drag` to be notified about the movements.
+    pub fn make_draggable(
+        &self,
+        handle_selector: Option<&str>,
+        bounds: Option<Rect>,
+    ) -> Result<(), DivError> {
+        state::exec_mut(|state| state.make_draggable(self, handle_selector, bounds))
+    }
+    /// Let the user resize the div by dragging the selected edges with a pointer.
+    ///
+    /// The size is limited by min and max, in the original scale when initializing.
+    /// Use `on_drag` to be notified about size changes.
+    pub fn make_resizable(
+        &self,
+        edges: Edges,
+        min: (u32, u32),
+        max: Option<(u32, u32)>,
+    ) -> Result<(), DivError> {
+        state::exec_mut(|state| state.make_resizable(self, edges, min, max))
     }
-    /// Set CSS property of div
+    /// Register a callback that is called when the user starts, continues or ends dragging or resizing the div.
+    /// Replaces any previously registered callback.
+    pub fn on_drag<F>(&self, callback: F) -> Result<(), DivError>
+    where
+        F: FnMut(DragEvent) + 'static,
+    {
+        let callback = Rc::new(RefCell::new(callback));
+        state::exec_mut(|state| state.set_drag_callback(self, callback))
+    }
+    /// Set CSS property of div.
+    ///
+    /// The properties `position`, `left`, `top`, `right`, `bottom`, `inset`, `width` and `height`, as well as
+    /// the minimum and maximum sizes and the logical equivalents like `inline-size`, are controlled by div
+    /// and cannot be set this way. Use `reposition`, `resize` and `set_size_limits` instead.
+    /// The same goes for `z-index`, which is set with `set_z`.
     pub fn set_css(&self, property: &str, value: &str) -> Result<(), DivError> {
         state::exec(|state| state.nodes.get(self)?.set_css(property, value))
     }
+    /// Get the value of an inline CSS property of the div, None if it is not set
+    pub fn get_css(&self, property: &str) -> Result<Option<String>, DivError> {
+        state::exec(|state| state.nodes.get(self)?.css(property))
+    }
+    /// Remove an inline CSS property from the div. The same properties as in `set_css` are protected.
+    pub fn remove_css(&self, property: &str) -> Result<(), DivError> {
+        state::exec(|state| state.nodes.get(self)?.remove_css(property))
+    }
+    /// Set all CSS properties of a typed style on the div. Properties not included in the style are left unchanged.
+    pub fn set_style(&self, style: &Style) -> Result<(), DivError> {
+        state::exec(|state| {
+            let pane = state.nodes.get(self)?;
+            for (property, value) in style.properties() {
+                pane.set_css(property, value)?;
+            }
+            Ok(())
+        })
+    }
+    /// Add a style-sheet whose rules only apply to the content of this div.
+    ///
+    /// All selectors are scoped to the div, use `:scope` or `&` to select the div itself.
+    /// The style-sheet is removed from the document when the div is deleted.
+    /// For divs with a shadow root, the style-sheet is placed inside the shadow root.
+    /// # Example
+    /// ```no_run
+    /// let div = div::new(0, 0, 200, 100, "<p>Hello</p>").unwrap();
+    /// div.add_scoped_css("p { color: red; } :scope { background: black; }").unwrap();
+    /// ```
+    pub fn add_scoped_css(&self, css: &str) -> Result<(), DivError> {
+        state::exec_mut(|state| state.add_scoped_css(self, css))
+    }
     /// Add a CSS class to the div
     pub fn add_class(&self, css_class: &str) -> Result<(), DivError> {
         state::exec(|state| state.nodes.get(self)?.add_class(css_class))
@@ -58,20 +278,109 @@ impl DivHandle {
     pub fn remove_class(&self, css_class: &str) -> Result<(), DivError> {
         state::exec(|state| state.nodes.get(self)?.remove_class(css_class))
     }
-    /// Removes a div from the DOM and deletes it
+    /// Check whether the div has a CSS class
+    pub fn has_class(&self, css_class: &str) -> Result<bool, DivError> {
+        state::exec(|state| Ok(state.nodes.get(self)?.has_class(css_class)))
+    }
+    /// Add the CSS class if the div does not have it, remove it otherwise.
+    /// Returns whether the div has the class afterwards.
+    pub fn toggle_class(&self, css_class: &str) -> Result<bool, DivError> {
+        state::exec(|state| state.nodes.get(self)?.toggle_class(css_class))
+    }
+    /// All CSS classes of the div.
+    ///
+    /// Classes that div uses internally, like `div-rs`, are not included.
+    /// They cannot be added or removed through the class functions either.
+    pub fn classes(&self) -> Result<Vec<String>, DivError> {
+        state::exec(|state| Ok(state.nodes.get(self)?.classes()))
+    }
+    /// Replace all CSS classes of the div with the given classes. Classes used internally by div are kept.
+    pub fn replace_classes(&self, classes: &[&str]) -> Result<(), DivError> {
+        state::exec(|state| state.nodes.get(self)?.replace_classes(classes))
+    }
+    /// Replaces the HTML content of the div.
+    ///
+    /// Attributes set on the div itself, as well as its classes and styles, are not affected.
+    /// Child divs are kept as well.
+    pub fn set_html(&self, html: &str) -> Result<(), DivError> {
+        state::exec_mut(|state| state.set_pane_html(self, html))
+    }
+    /// Set an HTML attribute on the div.
+    ///
+    /// The attributes `class` and `style` are managed by div and cannot be set this way,
+    /// use `add_class` and `set_css` instead.
+    pub fn set_attribute(&self, name: &str, value: &str) -> Result<(), DivError> {
+        state::exec_mut(|state| state.nodes.get_mut(self)?.set_attribute(name, value))
+    }
+    /// Remove an HTML attribute from the div
+    pub fn remove_attribute(&self, name: &str) -> Result<(), DivError> {
+        state::exec_mut(|state| state.nodes.get_mut(self)?.remove_attribute(name))
+    }
+    /// Get the value of an HTML attribute previously set through div.
+    pub fn attribute(&self, name: &str) -> Result<Option<String>, DivError> {
+        state::exec(|state| Ok(state.nodes.get(self)?.attribute(name).map(str::to_owned)))
+    }
+    /// Set the HTML id of the div
+    pub fn set_id(&self, id: &str) -> Result<(), DivError> {
+        self.set_attribute("id", id)
+    }
+    /// Set the ARIA role of the div, e.g. "dialog" or "status"
+    pub fn set_role(&self, role: &str) -> Result<(), DivError> {
+        self.set_attribute("role", role)
+    }
+    /// Set the `aria-label` of the div
+    pub fn set_aria_label(&self, label: &str) -> Result<(), DivError> {
+        self.set_attribute("aria-label", label)
+    }
+    /// Set a data attribute on the div. The key is prefixed with `data-`, so `set_data("id", "5")` sets `data-id="5"`.
+    pub fn set_data(&self, key: &str, value: &str) -> Result<(), DivError> {
+        self.set_attribute(&format!("data-{}", key), value)
+    }
+    /// Set the `tabindex` of the div to make it focusable by keyboard
+    pub fn set_tabindex(&self, index: i32) -> Result<(), DivError> {
+        self.set_attribute("tabindex", &index.to_string())
+    }
+    /// Removes a div from the DOM and deletes it, together with all divs created with it as parent
     pub fn delete(&mut self) -> Result<(), DivError> {
         state::exec_mut(|state| state.delete_pane(self))
     }
     /// Get a reference to the DOM element associated with the div.
     /// The provided HTML when creating a new div will be the child node(s) of the returned element.
     pub fn parent_element(&self) -> Result<HtmlElement, DivError> {
-        state::exec(|state| state.get_node(&self).map(Clone::clone))
+        state::exec(|state| state.get_node(self).cloned())
     }
     /// Get a reference to the DOM node created by the provided HTML when creating the pane.
     /// If multiple nodes have been created, the first node is returned.
+    /// For divs with a shadow root, this is the first node inside the shadow root.
     pub fn first_inner_node(&self) -> Result<Node, DivError> {
-        self.parent_element()?
-            .first_child()
-            .ok_or(DivError::MissingChild)
+        state::exec(|state| Ok(state.nodes.get(self)?.content().first_child()))
+            .and_then(|node| node.ok_or(DivError::MissingChild))
+    }
+    /// Finds the first element in the content of the div that matches the CSS selector, cast to the requested type.
+    ///
+    /// The search covers the content of the div, including its shadow root if it has one, but not the div node itself.
+    /// # Example
+    /// ```no_run
+    /// let div = div::new(0, 0, 300, 200, "<canvas></canvas>").unwrap();
+    /// let canvas: web_sys::HtmlElement = div.query("canvas").unwrap();
+    /// ```
+    pub fn query<T: JsCast>(&self, selector: &str) -> Result<T, DivError> {
+        let element = state::exec(|state| state.nodes.get(self)?.query_selector(selector))?
+            .ok_or_else(|| DivError::NoMatch(selector.to_owned()))?;
+        element
+            .dyn_into::<T>()
+            .map_err(|_| DivError::WrongElementType(selector.to_owned()))
+    }
+    /// Finds all elements in the content of the div that match the CSS selector, in document order.
+    /// Fails if any of them cannot be cast to the requested type.
+    pub fn query_all<T: JsCast>(&self, selector: &str) -> Result<Vec<T>, DivError> {
+        let list = state::exec(|state| state.nodes.get(self)?.query_selector_all(selector))?;
+        (0..list.length())
+            .filter_map(|i| list.item(i))
+            .map(|node| {
+                node.dyn_into::<T>()
+                    .map_err(|_| DivError::WrongElementType(selector.to_owned()))
+            })
+            .collect()
     }
 }
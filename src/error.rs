@@ -5,6 +5,8 @@ use std::fmt;
 /// Generic error type for all library calls which may fail.
 ///
 /// Having a common type for all errors can vastly simplify all kinds of call-chains.
+/// New variants may be added in minor releases, matches on it need a wildcard arm.
+#[non_exhaustive]
 pub enum DivError {
     UseAfterDelete,
     NotAllocated,
@@ -21,6 +23,15 @@ pub enum DivError {
     JsError(String),
     JsCastError,
     UndefinedSize,
+    ReservedAttribute(String),
+    ReservedProperty(String),
+    ReservedClass(String),
+    MissingBinding(String),
+    NoMatch(String),
+    WrongElementType(String),
+    DeserializeError(String),
+    InvalidScene(String),
+    InvalidSnapshot(String),
 }
 
 impl fmt::Display for DivError {
@@ -50,6 +61,24 @@ impl fmt::Display for DivError {
                 write!(f, "DOM child is missing which has been inserted before."),
             DivError::UndefinedSize =>
                 write!(f, "Pane has no size."),
+            DivError::ReservedAttribute(name) =>
+                write!(f, "The attribute {} is managed by div and cannot be set directly. Use the CSS and class functions instead.", name),
+            DivError::ReservedProperty(name) =>
+                write!(f, "The CSS property {} is controlled by div. Use the functions to position and resize divs instead.", name),
+            DivError::ReservedClass(name) =>
+                write!(f, "The CSS class {} is managed by div internally and cannot be added or removed.", name),
+            DivError::MissingBinding(key) =>
+                write!(f, "No node with data-div-bind=\"{}\" found in the div.", key),
+            DivError::NoMatch(selector) =>
+                write!(f, "No element in the div matches the selector {}.", selector),
+            DivError::WrongElementType(selector) =>
+                write!(f, "The element matching the selector {} has a different type than requested.", selector),
+            DivError::DeserializeError(msg) =>
+                write!(f, "Deserialization failed: {}", msg),
+            DivError::InvalidScene(msg) =>
+                write!(f, "Invalid scene description: {}", msg),
+            DivError::InvalidSnapshot(msg) =>
+                write!(f, "Invalid snapshot: {}", msg),
             DivError::BrowserError(e) =>
                 write!(f, "A browser-call returned an error: {}", e),
             DivError::JsError(msg) =>
@@ -0,0 +1,49 @@
+use crate::DivError;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, EventTarget};
+
+/// An event listener registered on a DOM node, which is removed again when dropped.
+///
+/// Panes keep their listeners, so that they live exactly as long as the pane.
+pub(crate) struct EventListener {
+    target: EventTarget,
+    event: String,
+    closure: Closure<dyn FnMut(Event)>,
+}
+
+impl EventListener {
+    pub(crate) fn new<F>(target: &EventTarget, event: &str, f: F) -> Result<Self, DivError>
+    where
+        F: FnMut(Event) + 'static,
+    {
+        let closure = Closure::wrap(Box::new(f) as Box<dyn FnMut(Event)>);
+        target.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())?;
+        Ok(EventListener {
+            target: target.clone(),
+            event: event.to_owned(),
+            closure,
+        })
+    }
+    pub(crate) fn target(&self) -> &EventTarget {
+        &self.target
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let _ = self.target.remove_event_listener_with_callback(
+            &self.event,
+            self.closure.as_ref().unchecked_ref(),
+        );
+    }
+}
+
+impl fmt::Debug for EventListener {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventListener")
+            .field("event", &self.event)
+            .finish()
+    }
+}
@@ -0,0 +1,73 @@
+/// An axis-aligned rectangle in pixel coordinates.
+///
+/// Depending on the context, the coordinates are either logical, as used when creating divs,
+/// or screen coordinates, after applying the global origin and zoom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, w: u32, h: u32) -> Self {
+        Rect { x, y, w, h }
+    }
+    /// Exclusive right border
+    pub fn right(&self) -> i32 {
+        self.x + self.w as i32
+    }
+    /// Exclusive bottom border
+    pub fn bottom(&self) -> i32 {
+        self.y + self.h as i32
+    }
+    /// Checks if a point lies within the rectangle. The top and left borders are inclusive, bottom and right exclusive.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+    /// Checks if two rectangles share a non-empty area
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+/// Reference point on the frame (or on the parent div) from which the position of a div is measured.
+///
+/// For example, a div anchored at `BottomRight` with position (-10, -10) stays 10 pixels
+/// away from the bottom-right corner of the frame, regardless of the frame size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl Anchor {
+    /// The anchor point as fractions of width and height, e.g. (1.0, 0.5) for `CenterRight`
+    pub fn fraction(&self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::TopCenter => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::CenterLeft => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::CenterRight => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::BottomCenter => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
@@ -16,3 +16,92 @@ pub fn reposition(x: i32, y: i32) -> Result<(), DivError> {
 pub fn resize(w: u32, h: u32) -> Result<(), DivError> {
     state::exec_mut(|state| state.global_resize(w, h))
 }
+
+/// Defines how divs are hidden, unless a div has its own strategy set.
+///
+/// The new strategy is applied to all divs that are currently hidden, as well.
+/// See `HideStrategy` for the available options.
+pub fn set_hide_strategy(strategy: HideStrategy) -> Result<(), DivError> {
+    state::exec_mut(|state| {
+        state.hide_strategy = strategy;
+        for handle in state.nodes.handles() {
+            state.refresh_visibility(&handle)?;
+        }
+        Ok(())
+    })
+}
+
+/// The global origin, as defined by `div::init_ex` or `div::reposition`
+pub fn origin() -> Result<(i32, i32), DivError> {
+    state::exec(|state| Ok(state.pos))
+}
+
+/// The current size of the global frame, after all calls to `div::resize`.
+/// Returns None if no size has been defined when initializing.
+pub fn frame_size() -> Result<Option<(u32, u32)>, DivError> {
+    state::exec(|state| Ok(state.frame_size()))
+}
+
+/// The scaling factors (horizontal, vertical) applied to all divs, as a result of `div::resize`.
+pub fn zoom() -> Result<(f32, f32), DivError> {
+    state::exec(|state| Ok(state.zoom))
+}
+
+/// Handles to all divs that currently exist, hidden or not, in the order they have been created.
+pub fn all() -> Result<Vec<DivHandle>, DivError> {
+    state::exec(|state| Ok(state.nodes.handles()))
+}
+
+/// Handles to all divs that have the given CSS class, in the order they have been created.
+/// # Example
+/// ```no_run
+/// // Hide the entire HUD when entering a menu
+/// for div in div::with_class("hud").unwrap() {
+///     div.hide().unwrap();
+/// }
+/// ```
+pub fn with_class(css_class: &str) -> Result<Vec<DivHandle>, DivError> {
+    state::exec(|state| {
+        let mut out = vec![];
+        for handle in state.nodes.handles() {
+            if state.nodes.get(&handle)?.has_class(css_class) {
+                out.push(handle);
+            }
+        }
+        Ok(out)
+    })
+}
+
+/// Hides all divs. See `DivHandle::hide`.
+pub fn hide_all() -> Result<(), DivError> {
+    state::exec_mut(|state| {
+        for handle in state.nodes.handles() {
+            state.hide_pane(&handle)?;
+        }
+        Ok(())
+    })
+}
+
+/// Displays all divs that have been hidden. See `DivHandle::show`.
+pub fn show_all() -> Result<(), DivError> {
+    state::exec_mut(|state| {
+        for handle in state.nodes.handles() {
+            state.show_pane(&handle)?;
+        }
+        Ok(())
+    })
+}
+
+/// Removes all divs from the DOM and deletes them.
+/// All existing DivHandles become invalid.
+pub fn delete_all() -> Result<(), DivError> {
+    state::exec_mut(|state| {
+        for handle in state.nodes.handles() {
+            // Child divs have been deleted together with their parent already
+            if state.nodes.get(&handle).is_ok() {
+                state.delete_pane(&handle)?;
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,184 @@
+//! Groups of divs that share a transformation and visibility
+//!
+//! A group acts as an intermediate layer between the global frame and the divs in it.
+//! Divs in a group are positioned relative to the group's offset and scaled by the group's scale,
+//! on top of the global origin and zoom.
+
+use crate::state;
+use crate::storage::{IndexedMap, PaneStorage};
+use crate::*;
+
+/// A light-weight key to refer to a group of divs.
+///
+/// Create a new group with `div::new_group()` and add divs to it with `DivGroup::add`
+/// or `DivBuilder::group`.
+/// # Example
+/// ```no_run
+/// let hud = div::new_group().unwrap();
+/// let score = div::new(10, 10, 100, 20, "Score: 0").unwrap();
+/// hud.add(score).unwrap();
+/// // Moves the score together with all other divs of the HUD
+/// hud.set_offset(0, 50).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DivGroup(pub(crate) usize);
+
+/// Internal state of a group
+#[derive(Debug, Clone)]
+pub(crate) struct Group {
+    pub(crate) offset: (i32, i32),
+    pub(crate) scale: f32,
+    pub(crate) visible: bool,
+    pub(crate) camera: Option<Camera>,
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Group {
+            offset: (0, 0),
+            scale: 1.0,
+            visible: true,
+            camera: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct GroupStorage {
+    data: IndexedMap<Group>,
+}
+
+/// Creates a new, empty group with no offset and a scale of 1.0
+pub fn new_group() -> Result<DivGroup, DivError> {
+    state::exec_mut(|state| Ok(state.groups.insert(Group::default())))
+}
+
+impl DivGroup {
+    /// Adds a div to the group. A div can only be in one group, it is removed from its previous group, if any.
+    pub fn add(&self, div: DivHandle) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.groups.get(self)?;
+            state.set_pane_group(&div, Some(*self))
+        })
+    }
+    /// Removes a div from the group. The div is then positioned in the global frame directly.
+    pub fn remove(&self, div: DivHandle) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            if state.nodes.get(&div)?.group() == Some(*self) {
+                state.set_pane_group(&div, None)?;
+            }
+            Ok(())
+        })
+    }
+    /// All divs in the group, in the order they have been created
+    pub fn divs(&self) -> Result<Vec<DivHandle>, DivError> {
+        state::exec(|state| {
+            state.groups.get(self)?;
+            state.group_members(self)
+        })
+    }
+    /// Moves all divs in the group by the given offset, in the original scale of the global frame.
+    pub fn set_offset(&self, x: i32, y: i32) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.groups.get_mut(self)?.offset = (x, y);
+            state.redraw_group(self)
+        })
+    }
+    /// The offset of the group, as set by `set_offset`
+    pub fn offset(&self) -> Result<(i32, i32), DivError> {
+        state::exec(|state| Ok(state.groups.get(self)?.offset))
+    }
+    /// Scales positions and sizes of all divs in the group. The global zoom is applied on top of it.
+    pub fn set_scale(&self, scale: f32) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.groups.get_mut(self)?.scale = scale;
+            state.redraw_group(self)
+        })
+    }
+    /// The scale of the group, as set by `set_scale`
+    pub fn scale(&self) -> Result<f32, DivError> {
+        state::exec(|state| Ok(state.groups.get(self)?.scale))
+    }
+    /// Sets or removes the camera of the group. With a camera, the positions of divs in the group are interpreted as world coordinates.
+    /// See `Camera` for details.
+    pub fn set_camera(&self, camera: Option<Camera>) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.groups.get_mut(self)?.camera = camera;
+            state.redraw_group(self)
+        })
+    }
+    /// The camera of the group, if any
+    pub fn camera(&self) -> Result<Option<Camera>, DivError> {
+        state::exec(|state| Ok(state.groups.get(self)?.camera))
+    }
+    /// Hides all divs in the group.
+    ///
+    /// Divs that are shown individually with `DivHandle::show` remain hidden until the group is shown again.
+    pub fn hide(&self) -> Result<(), DivError> {
+        self.set_visible(false)
+    }
+    /// Displays the divs in the group again, except those that have been hidden individually.
+    pub fn show(&self) -> Result<(), DivError> {
+        self.set_visible(true)
+    }
+    /// Checks whether the group is visible
+    pub fn is_visible(&self) -> Result<bool, DivError> {
+        state::exec(|state| Ok(state.groups.get(self)?.visible))
+    }
+    /// Deletes the group. The divs in it are not deleted but moved to the global frame.
+    pub fn delete(&mut self) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            for div in state.group_members(self)? {
+                state.set_pane_group(&div, None)?;
+            }
+            state.groups.remove(self)?;
+            Ok(())
+        })
+    }
+    fn set_visible(&self, visible: bool) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            state.groups.get_mut(self)?.visible = visible;
+            for div in state.group_members(self)? {
+                state.refresh_visibility(&div)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<PS: PaneStorage, CS: ClassStorage> GlobalState<PS, CS> {
+    pub(crate) fn group_members(&self, group: &DivGroup) -> Result<Vec<DivHandle>, DivError> {
+        let mut out = vec![];
+        for handle in self.nodes.handles() {
+            if self.nodes.get(&handle)?.group() == Some(*group) {
+                out.push(handle);
+            }
+        }
+        Ok(out)
+    }
+    fn set_pane_group(&mut self, p: &DivHandle, group: Option<DivGroup>) -> Result<(), DivError> {
+        self.nodes.get_mut(p)?.set_group(group);
+        self.redraw_pane(p)
+    }
+    fn redraw_group(&mut self, group: &DivGroup) -> Result<(), DivError> {
+        for div in self.group_members(group)? {
+            self.redraw_pane(&div)?;
+        }
+        Ok(())
+    }
+}
+
+impl GroupStorage {
+    fn insert(&mut self, group: Group) -> DivGroup {
+        DivGroup(self.data.insert(group))
+    }
+    fn remove(&mut self, g: &DivGroup) -> Result<Group, DivError> {
+        self.data.remove(g.0)
+    }
+    pub(crate) fn get(&self, g: &DivGroup) -> Result<&Group, DivError> {
+        self.data.get(g.0)
+    }
+    pub(crate) fn get_mut(&mut self, g: &DivGroup) -> Result<&mut Group, DivError> {
+        self.data.get_mut(g.0)
+    }
+}
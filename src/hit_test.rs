@@ -0,0 +1,122 @@
+//! Hit testing: finding divs by coordinates in the global frame
+//!
+//! Frame coordinates are the logical coordinates used to create and position divs.
+//! They are independent of the global origin and of the zoom applied with `div::resize`.
+
+use crate::state;
+use crate::storage::PaneStorage;
+use crate::*;
+
+/// Finds the top-most visible div at the given point in frame coordinates.
+///
+/// Hidden divs (and children of hidden divs) are ignored.
+/// Among divs with the same z-index, the one created last is considered to be on top.
+/// # Example
+/// ```no_run
+/// # fn on_click(client_x: i32, client_y: i32) {
+/// let (x, y) = div::client_to_frame(client_x, client_y).unwrap();
+/// if div::div_at(x, y).unwrap().is_none() {
+///     // forward event to the canvas
+/// }
+/// # }
+/// ```
+pub fn div_at(x: i32, y: i32) -> Result<Option<DivHandle>, DivError> {
+    state::exec(|state| {
+        let (x, y) = state.frame_to_screen(x, y);
+        let hits = state.divs_on_screen(|rect| rect.contains(x, y))?;
+        Ok(hits.first().copied())
+    })
+}
+
+/// Finds all visible divs that overlap with the given rectangle in frame coordinates.
+///
+/// The result is ordered from top-most to bottom-most.
+pub fn divs_in_rect(rect: Rect) -> Result<Vec<DivHandle>, DivError> {
+    state::exec(|state| {
+        let (x, y) = state.frame_to_screen(rect.x, rect.y);
+        let screen = Rect::new(
+            x,
+            y,
+            (rect.w as f32 * state.zoom.0) as u32,
+            (rect.h as f32 * state.zoom.1) as u32,
+        );
+        state.divs_on_screen(|r| r.intersects(&screen))
+    })
+}
+
+/// Converts client coordinates, as found in browser events like `MouseEvent.clientX`, to frame coordinates.
+///
+/// The conversion is based on the position of the root element in the viewport.
+/// For correct results, the root element should be the containing block of the divs, e.g. by making it `position: relative`.
+pub fn client_to_frame(client_x: i32, client_y: i32) -> Result<(i32, i32), DivError> {
+    state::exec(|state| {
+        let root = state.root.get_bounding_client_rect();
+        let x = client_x as f64 - root.left();
+        let y = client_y as f64 - root.top();
+        Ok(state.screen_to_frame(x.round() as i32, y.round() as i32))
+    })
+}
+
+/// Converts frame coordinates to client coordinates, the inverse of `client_to_frame`.
+pub fn frame_to_client(x: i32, y: i32) -> Result<(i32, i32), DivError> {
+    state::exec(|state| {
+        let root = state.root.get_bounding_client_rect();
+        let (x, y) = state.frame_to_screen(x, y);
+        Ok((
+            (x as f64 + root.left()).round() as i32,
+            (y as f64 + root.top()).round() as i32,
+        ))
+    })
+}
+
+impl<PS: PaneStorage, CS: ClassStorage> GlobalState<PS, CS> {
+    /// Converts frame coordinates to pixels relative to the root element
+    pub(crate) fn frame_to_screen(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            self.pos.0 + (x as f32 * self.zoom.0) as i32,
+            self.pos.1 + (y as f32 * self.zoom.1) as i32,
+        )
+    }
+    /// Converts pixels relative to the root element to frame coordinates
+    pub(crate) fn screen_to_frame(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            ((x - self.pos.0) as f32 / self.zoom.0) as i32,
+            ((y - self.pos.1) as f32 / self.zoom.1) as i32,
+        )
+    }
+    /// All visible panes for which the filter on their screen rectangle returns true, top-most first
+    fn divs_on_screen<F>(&self, filter: F) -> Result<Vec<DivHandle>, DivError>
+    where
+        F: Fn(&Rect) -> bool,
+    {
+        let mut hits = vec![];
+        for handle in self.nodes.handles() {
+            if self.is_visible_on_screen(&handle)? && filter(&self.screen_rect(&handle)?) {
+                hits.push((self.stacking_order(&handle)?, handle));
+            }
+        }
+        hits.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(hits.into_iter().map(|(_, handle)| handle).collect())
+    }
+    /// A pane is only visible if itself and all its ancestors are displayed
+    fn is_visible_on_screen(&self, p: &DivHandle) -> Result<bool, DivError> {
+        let pane = self.nodes.get(p)?;
+        match pane.parent() {
+            _ if !pane.is_shown() => Ok(false),
+            Some(parent) => self.is_visible_on_screen(&parent),
+            None => Ok(true),
+        }
+    }
+    /// Key to sort panes by how they are stacked on the screen.
+    ///
+    /// Each pane creates its own stacking context, hence the z-index of all ancestors is considered first.
+    fn stacking_order(&self, p: &DivHandle) -> Result<Vec<(i32, DivHandle)>, DivError> {
+        let pane = self.nodes.get(p)?;
+        let mut order = match pane.parent() {
+            Some(parent) => self.stacking_order(&parent)?,
+            None => vec![],
+        };
+        order.push((pane.z(), *p));
+        Ok(order)
+    }
+}
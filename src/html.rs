@@ -0,0 +1,283 @@
+//! HTML templates that escape interpolated values
+//!
+//! `Html` holds markup that is trusted to be inserted as it is. Values are turned into markup with `ToHtml`,
+//! which escapes text by default. Use the `html!` macro to interpolate values into a template.
+
+use std::fmt;
+
+/// A piece of HTML that is inserted into the DOM as it is.
+///
+/// A reference to it can be passed to `div::new`, `div::new_styled`, `DivHandle::set_html` and everywhere else HTML is expected as `&str`.
+/// Create it with the `html!` macro, which escapes all interpolated values,
+/// with `Html::text` for escaped plain text, or with `Html::raw` to mark a string as trusted markup.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Html(String);
+
+/// Conversion of a value into HTML, escaping everything that is not already `Html`
+pub trait ToHtml {
+    fn to_html(&self) -> Html;
+}
+
+/// Creates `Html` from a template, with each `{}` replaced by the next argument converted with `ToHtml`.
+///
+/// Strings and other values are escaped, an `Html` argument is inserted as it is.
+/// Literal braces are written as `{{` and `}}`.
+/// Only positional `{}` placeholders are supported, named arguments or format specifiers are not.
+/// The template is checked when compiling, a template that does not match the number of arguments
+/// or contains an invalid placeholder fails to compile.
+/// # Example
+/// ```no_run
+/// use div::{html, Html};
+/// let name = "<script>alert('hi')</script>";
+/// let icon = Html::raw("<img src='user.png'>");
+/// let content = html!("<p>{} Hello {}, you have {} messages</p>", icon, name, 3);
+/// div::new(0, 0, 300, 50, &content).unwrap();
+/// ```
+#[macro_export]
+macro_rules! html {
+    ($template:literal $(, $arg:expr)* $(,)?) => {{
+        const _: () = match $crate::Html::check_template(
+            $template,
+            <[&str]>::len(&[$(stringify!($arg)),*]),
+        ) {
+            Ok(()) => (),
+            Err(msg) => panic!("{}", msg),
+        };
+        $crate::Html::from_template($template, &[$(&$arg as &dyn $crate::ToHtml),*])
+    }};
+}
+
+impl Html {
+    /// Marks a string as trusted HTML, without escaping. Never use this with content from untrusted sources.
+    pub fn raw(html: impl Into<String>) -> Self {
+        Html(html.into())
+    }
+    /// Escapes a string to be displayed as plain text
+    pub fn text(text: &str) -> Self {
+        Html(escape(text))
+    }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    pub fn into_string(self) -> String {
+        self.0
+    }
+    /// Appends escaped text or other HTML
+    pub fn push(&mut self, content: &dyn ToHtml) {
+        self.0 += content.to_html().as_str();
+    }
+    /// Used by the `html!` macro to check the template when compiling
+    #[doc(hidden)]
+    pub const fn check_template(template: &str, args: usize) -> Result<(), &'static str> {
+        let bytes = template.as_bytes();
+        let mut placeholders = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let next = if i + 1 < bytes.len() { bytes[i + 1] } else { 0 };
+            match (bytes[i], next) {
+                (b'{', b'}') => {
+                    placeholders += 1;
+                    i += 2;
+                }
+                (b'{', b'{') | (b'}', b'}') => i += 2,
+                (b'{', _) | (b'}', _) => {
+                    return Err("invalid placeholder in html! template, only {} is supported")
+                }
+                _ => i += 1,
+            }
+        }
+        if placeholders > args {
+            Err("html! template has more placeholders than arguments")
+        } else if placeholders < args {
+            Err("html! template has more arguments than placeholders")
+        } else {
+            Ok(())
+        }
+    }
+    /// Used by the `html!` macro, after checking the template with `check_template`
+    #[doc(hidden)]
+    pub fn from_template(template: &str, args: &[&dyn ToHtml]) -> Self {
+        let mut out = String::with_capacity(template.len());
+        let mut args = args.iter();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    out.push(c);
+                }
+                ('{', Some('}')) => {
+                    chars.next();
+                    let arg = args
+                        .next()
+                        .expect("html! template has more placeholders than arguments");
+                    out += arg.to_html().as_str();
+                }
+                ('{', _) | ('}', _) => panic!(
+                    "invalid placeholder in html! template, only {{}} is supported: {}",
+                    template
+                ),
+                _ => out.push(c),
+            }
+        }
+        assert!(
+            args.next().is_none(),
+            "html! template has more arguments than placeholders"
+        );
+        Html(out)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out += "&amp;",
+            '<' => out += "&lt;",
+            '>' => out += "&gt;",
+            '"' => out += "&quot;",
+            '\'' => out += "&#39;",
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+impl fmt::Display for Html {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Allows passing `&Html` to all functions taking HTML as `&str`
+impl std::ops::Deref for Html {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Html {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Html> for String {
+    fn from(html: Html) -> String {
+        html.0
+    }
+}
+
+impl From<&Html> for String {
+    fn from(html: &Html) -> String {
+        html.0.clone()
+    }
+}
+
+impl ToHtml for Html {
+    fn to_html(&self) -> Html {
+        self.clone()
+    }
+}
+
+impl ToHtml for str {
+    fn to_html(&self) -> Html {
+        Html::text(self)
+    }
+}
+
+impl ToHtml for String {
+    fn to_html(&self) -> Html {
+        Html::text(self)
+    }
+}
+
+impl<T: ToHtml + ?Sized> ToHtml for &T {
+    fn to_html(&self) -> Html {
+        (**self).to_html()
+    }
+}
+
+impl<T: ToHtml> ToHtml for Option<T> {
+    /// None is rendered as nothing
+    fn to_html(&self) -> Html {
+        self.as_ref().map(ToHtml::to_html).unwrap_or_default()
+    }
+}
+
+macro_rules! display_to_html {
+    ($($t:ty),*) => {
+        $(
+            impl ToHtml for $t {
+                fn to_html(&self) -> Html {
+                    Html::text(&self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+display_to_html!(
+    char, bool, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(
+            Html::text(r#"<a href="x">Tom & 'Jerry'</a>"#).as_str(),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(Html::text("plain text ✓").as_str(), "plain text ✓");
+    }
+
+    #[test]
+    fn interpolates_escaped_values() {
+        let name = "<b>";
+        assert_eq!(
+            crate::html!("<p>{}: {}</p>", name, 3).as_str(),
+            "<p>&lt;b&gt;: 3</p>"
+        );
+        assert_eq!(crate::html!("{{{}}}", "&").as_str(), "{&amp;}");
+        assert_eq!(crate::html!("}} {{").as_str(), "} {");
+    }
+
+    #[test]
+    fn passes_html_and_options_through() {
+        let icon = Html::raw("<img src='a.png'>");
+        let missing: Option<&str> = None;
+        assert_eq!(
+            crate::html!("{}{}{}", icon, missing, Some("<")).as_str(),
+            "<img src='a.png'>&lt;"
+        );
+        let mut html = Html::raw("<br>");
+        html.push(&"<br>");
+        html.push(&Html::raw("<hr>"));
+        assert_eq!(html.as_str(), "<br>&lt;br&gt;<hr>");
+    }
+
+    #[test]
+    fn rejects_mismatching_templates() {
+        assert_eq!(Html::check_template("{} {{}} {}", 2), Ok(()));
+        assert_eq!(
+            Html::check_template("{} {}", 1),
+            Err("html! template has more placeholders than arguments")
+        );
+        assert_eq!(
+            Html::check_template("{}", 2),
+            Err("html! template has more arguments than placeholders")
+        );
+        assert_eq!(
+            Html::check_template("{name}", 1),
+            Err("invalid placeholder in html! template, only {} is supported")
+        );
+        assert_eq!(
+            Html::check_template("}", 0),
+            Err("invalid placeholder in html! template, only {} is supported")
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Access to form fields in the content of a div
+//!
+//! `<input>`, `<select>` and `<textarea>` elements are wrapped in an `InputHandle`,
+//! which hides the differences between the three element types.
+
+use crate::events::EventListener;
+use crate::state;
+use crate::*;
+use std::collections::BTreeMap;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+
+/// Refers to a form field in the content of a div, obtained with `DivHandle::input`.
+/// # Example
+/// ```no_run
+/// let div = div::new(0, 0, 300, 50, r#"<input name="player" value="Alice">"#).unwrap();
+/// let input = div.input("input[name=player]").unwrap();
+/// input.on_input(|value| println!("Typed: {}", value)).unwrap();
+/// input.set_value("Bob").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputHandle {
+    div: DivHandle,
+    field: Field,
+}
+
+#[derive(Debug, Clone)]
+enum Field {
+    Input(HtmlInputElement),
+    Select(HtmlSelectElement),
+    TextArea(HtmlTextAreaElement),
+}
+
+impl DivHandle {
+    /// Finds the first `<input>`, `<select>` or `<textarea>` element in the div that matches the selector
+    pub fn input(&self, selector: &str) -> Result<InputHandle, DivError> {
+        let element: Element = self.query(selector)?;
+        Ok(InputHandle {
+            div: *self,
+            field: Field::from_element(element)
+                .ok_or_else(|| DivError::WrongElementType(selector.to_owned()))?,
+        })
+    }
+    /// Collects the values of all named form fields in the div, by their name.
+    ///
+    /// Like an HTML form, disabled fields as well as checkboxes and radio buttons that are not checked are left out.
+    /// If several fields have the same name, the last one wins.
+    pub fn form_data(&self) -> Result<BTreeMap<String, String>, DivError> {
+        let mut data = BTreeMap::new();
+        for (name, field) in self.named_fields()? {
+            if field.is_submitted() {
+                data.insert(name, field.value());
+            }
+        }
+        Ok(data)
+    }
+    /// Deserializes the named form fields in the div into a struct, with the field names as keys.
+    ///
+    /// Checkboxes are read as booleans, number and range inputs as numbers (integers if possible, or null when empty),
+    /// and all other fields as strings. Disabled fields and unchecked radio buttons are left out.
+    #[cfg(feature = "serde")]
+    pub fn form_data_as<T: serde::de::DeserializeOwned>(&self) -> Result<T, DivError> {
+        use serde_json::Value;
+        let mut data = serde_json::Map::new();
+        for (name, field) in self.named_fields()? {
+            let value = match field.input_type().as_deref() {
+                Some("checkbox") if !field.is_disabled() => Value::Bool(field.checked()),
+                Some("number") | Some("range") if field.is_submitted() => {
+                    number_value(&field.value())
+                }
+                _ if field.is_submitted() => Value::String(field.value()),
+                _ => continue,
+            };
+            data.insert(name, value);
+        }
+        serde_json::from_value(Value::Object(data))
+            .map_err(|e| DivError::DeserializeError(e.to_string()))
+    }
+    fn named_fields(&self) -> Result<Vec<(String, Field)>, DivError> {
+        let elements: Vec<Element> = self.query_all("input[name], select[name], textarea[name]")?;
+        Ok(elements
+            .into_iter()
+            .filter_map(|element| {
+                let name = element.get_attribute("name")?;
+                Some((name, Field::from_element(element)?))
+            })
+            .collect())
+    }
+}
+
+impl InputHandle {
+    /// The current value, for a `<select>` the value of the selected option
+    pub fn value(&self) -> String {
+        self.field.value()
+    }
+    pub fn set_value(&self, value: &str) -> Result<(), DivError> {
+        match &self.field {
+            Field::Input(e) => e.set_value(value),
+            Field::Select(e) => e.set_value(value),
+            Field::TextArea(e) => e.set_value(value),
+        }
+        Ok(())
+    }
+    /// Whether a checkbox or radio button is checked, false for all other fields
+    pub fn checked(&self) -> bool {
+        self.field.checked()
+    }
+    /// Checks or unchecks a checkbox or radio button. Other fields are not affected.
+    pub fn set_checked(&self, checked: bool) -> Result<(), DivError> {
+        if let Field::Input(e) = &self.field {
+            e.set_checked(checked);
+        }
+        Ok(())
+    }
+    /// Calls the closure with the new value when the user commits a change, e.g. by leaving a text field or selecting an option.
+    ///
+    /// The closure is dropped with the div, or when the field is removed by replacing the content of the div.
+    pub fn on_change<F>(&self, f: F) -> Result<(), DivError>
+    where
+        F: FnMut(String) + 'static,
+    {
+        self.listen("change", f)
+    }
+    /// Calls the closure with the new value on every change, e.g. on every key stroke in a text field.
+    pub fn on_input<F>(&self, f: F) -> Result<(), DivError>
+    where
+        F: FnMut(String) + 'static,
+    {
+        self.listen("input", f)
+    }
+    /// The DOM element of the field
+    pub fn element(&self) -> HtmlElement {
+        match &self.field {
+            Field::Input(e) => e.clone().into(),
+            Field::Select(e) => e.clone().into(),
+            Field::TextArea(e) => e.clone().into(),
+        }
+    }
+    pub fn div(&self) -> DivHandle {
+        self.div
+    }
+    fn listen<F>(&self, event: &str, mut f: F) -> Result<(), DivError>
+    where
+        F: FnMut(String) + 'static,
+    {
+        let field = self.field.clone();
+        let listener = EventListener::new(&self.element(), event, move |_| f(field.value()))?;
+        state::exec_mut(|state| {
+            state
+                .nodes
+                .get_mut(&self.div)?
+                .add_content_listener(listener);
+            Ok(())
+        })
+    }
+}
+
+/// Converts the text of a number field to JSON, as an integer if possible such that it can be read into integer fields.
+/// Empty or invalid input is null.
+#[cfg(feature = "serde")]
+fn number_value(text: &str) -> serde_json::Value {
+    use serde_json::{Number, Value};
+    let text = text.trim();
+    if let Ok(n) = text.parse::<u64>() {
+        return Value::Number(n.into());
+    }
+    if let Ok(n) = text.parse::<i64>() {
+        return Value::Number(n.into());
+    }
+    text.parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+impl Field {
+    fn from_element(element: Element) -> Option<Self> {
+        let element = match element.dyn_into::<HtmlInputElement>() {
+            Ok(e) => return Some(Field::Input(e)),
+            Err(element) => element,
+        };
+        let element = match element.dyn_into::<HtmlSelectElement>() {
+            Ok(e) => return Some(Field::Select(e)),
+            Err(element) => element,
+        };
+        element
+            .dyn_into::<HtmlTextAreaElement>()
+            .ok()
+            .map(Field::TextArea)
+    }
+    fn value(&self) -> String {
+        match self {
+            Field::Input(e) => e.value(),
+            Field::Select(e) => e.value(),
+            Field::TextArea(e) => e.value(),
+        }
+    }
+    fn checked(&self) -> bool {
+        match self {
+            Field::Input(e) => e.checked(),
+            _ => false,
+        }
+    }
+    fn input_type(&self) -> Option<String> {
+        match self {
+            Field::Input(e) => Some(e.type_().to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+    fn is_disabled(&self) -> bool {
+        match self {
+            Field::Input(e) => e.disabled(),
+            Field::Select(e) => e.disabled(),
+            Field::TextArea(e) => e.disabled(),
+        }
+    }
+    /// Whether the field would be included when submitting a form
+    fn is_submitted(&self) -> bool {
+        if self.is_disabled() {
+            return false;
+        }
+        match self.input_type().as_deref() {
+            Some("checkbox") | Some("radio") => self.checked(),
+            Some("button") | Some("submit") | Some("reset") | Some("image") | Some("file") => false,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_numbers_for_integer_fields() {
+        assert_eq!(number_value("3"), json!(3));
+        assert_eq!(number_value("-12"), json!(-12));
+        assert_eq!(number_value("18446744073709551615"), json!(u64::MAX));
+        assert_eq!(number_value("2.5"), json!(2.5));
+        assert_eq!(number_value("1e3"), json!(1000.0));
+        assert_eq!(number_value(""), json!(null));
+        assert_eq!(number_value("abc"), json!(null));
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Form {
+            age: u32,
+            offset: i8,
+            weight: f32,
+        }
+        let form = json!({
+            "age": number_value("42"),
+            "offset": number_value("-3"),
+            "weight": number_value("70"),
+        });
+        assert_eq!(
+            serde_json::from_value::<Form>(form).unwrap(),
+            Form {
+                age: 42,
+                offset: -3,
+                weight: 70.0
+            }
+        );
+    }
+}
@@ -0,0 +1,504 @@
+//! Dragging and resizing of divs by the user, with pointer events
+//!
+//! The pointer events are registered on the pane node once, when the first interaction is enabled.
+//! All geometry updates go through the usual pane update, in unscaled frame units.
+
+use crate::events::EventListener;
+use crate::pane::Placement;
+use crate::state;
+use crate::storage::PaneStorage;
+use crate::*;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Event, PointerEvent};
+
+/// Distance in pixels from the border of a div within which a pointer starts resizing instead of dragging
+const GRIP_SIZE: f64 = 8.0;
+
+/// Selects borders of a div, e.g. those that can be used for resizing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Edges {
+    pub top: bool,
+    pub right: bool,
+    pub bottom: bool,
+    pub left: bool,
+}
+
+/// Whether the user is moving or resizing a div
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragKind {
+    Move,
+    Resize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragPhase {
+    Start,
+    Move,
+    End,
+}
+
+/// Passed to the callback registered with `DivHandle::on_drag`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DragEvent {
+    pub kind: DragKind,
+    pub phase: DragPhase,
+    /// Position and size of the div after the event, in the original scale when initializing
+    pub rect: Rect,
+}
+
+type DragCallback = Rc<RefCell<dyn FnMut(DragEvent)>>;
+type PointerHandler = fn(&RefCell<Interaction>, PointerEvent);
+
+/// Interaction state of a single pane, shared between the pane and its event listeners
+pub(crate) struct Interaction {
+    div: DivHandle,
+    drag: Option<DragConfig>,
+    resize: Option<ResizeConfig>,
+    active: Option<ActiveDrag>,
+    callback: Option<DragCallback>,
+    /// Class currently showing a resize cursor while hovering an edge
+    cursor_class: Option<&'static str>,
+}
+
+#[derive(Debug)]
+struct DragConfig {
+    handle_selector: Option<String>,
+    bounds: Option<Rect>,
+}
+
+#[derive(Debug)]
+struct ResizeConfig {
+    edges: Edges,
+    min: (u32, u32),
+    max: Option<(u32, u32)>,
+}
+
+#[derive(Debug)]
+struct ActiveDrag {
+    kind: DragKind,
+    edges: Edges,
+    start: (i32, i32),
+    start_rect: Rect,
+    scale: (f32, f32),
+    /// Converts the position of the div to the rectangle it covers in the frame, where moving and resizing is computed
+    placement: Placement,
+}
+
+impl Edges {
+    pub const NONE: Edges = Edges {
+        top: false,
+        right: false,
+        bottom: false,
+        left: false,
+    };
+    pub const ALL: Edges = Edges {
+        top: true,
+        right: true,
+        bottom: true,
+        left: true,
+    };
+    pub const BOTTOM_RIGHT: Edges = Edges {
+        top: false,
+        right: true,
+        bottom: true,
+        left: false,
+    };
+    pub fn any(&self) -> bool {
+        self.top || self.right || self.bottom || self.left
+    }
+    /// The edges of a bounding box close to a point in client coordinates, limited to the edges in self
+    fn hit(&self, bounds: &web_sys::DomRect, x: i32, y: i32) -> Edges {
+        let (x, y) = (x as f64, y as f64);
+        Edges {
+            top: self.top && (y - bounds.top()).abs() <= GRIP_SIZE,
+            right: self.right && (x - bounds.right()).abs() <= GRIP_SIZE,
+            bottom: self.bottom && (y - bounds.bottom()).abs() <= GRIP_SIZE,
+            left: self.left && (x - bounds.left()).abs() <= GRIP_SIZE,
+        }
+    }
+    /// The class showing the resize cursor for these edges, defined in the div-rs stylesheet
+    fn cursor_class(&self) -> Option<&'static str> {
+        match (self.top, self.right, self.bottom, self.left) {
+            (true, true, _, _) | (_, _, true, true) => Some("div-rs-resize-nesw"),
+            (true, _, _, true) | (_, true, true, _) => Some("div-rs-resize-nwse"),
+            (true, _, _, _) | (_, _, true, _) => Some("div-rs-resize-ns"),
+            (_, true, _, _) | (_, _, _, true) => Some("div-rs-resize-ew"),
+            _ => None,
+        }
+    }
+}
+
+impl<PS: PaneStorage, CS: ClassStorage> GlobalState<PS, CS> {
+    pub(crate) fn make_draggable(
+        &mut self,
+        p: &DivHandle,
+        handle_selector: Option<&str>,
+        bounds: Option<Rect>,
+    ) -> Result<(), DivError> {
+        self.interaction(p)?.borrow_mut().drag = Some(DragConfig {
+            handle_selector: handle_selector.map(str::to_owned),
+            bounds,
+        });
+        Ok(())
+    }
+    pub(crate) fn make_resizable(
+        &mut self,
+        p: &DivHandle,
+        edges: Edges,
+        min: (u32, u32),
+        max: Option<(u32, u32)>,
+    ) -> Result<(), DivError> {
+        self.interaction(p)?.borrow_mut().resize = Some(ResizeConfig { edges, min, max });
+        Ok(())
+    }
+    pub(crate) fn set_drag_callback(
+        &mut self,
+        p: &DivHandle,
+        callback: DragCallback,
+    ) -> Result<(), DivError> {
+        self.interaction(p)?.borrow_mut().callback = Some(callback);
+        Ok(())
+    }
+    /// Returns the interaction state of the pane, registering the pointer event listeners if necessary
+    fn interaction(&mut self, p: &DivHandle) -> Result<Rc<RefCell<Interaction>>, DivError> {
+        let pane = self.nodes.get_mut(p)?;
+        if let Some(interaction) = pane.interaction() {
+            return Ok(interaction);
+        }
+        let interaction = Rc::new(RefCell::new(Interaction {
+            div: *p,
+            drag: None,
+            resize: None,
+            active: None,
+            callback: None,
+            cursor_class: None,
+        }));
+        let node = pane.node().clone();
+        // Otherwise, touch devices start scrolling and cancel the pointer events
+        node.style().set_property("touch-action", "none")?;
+        let handlers: [(&str, PointerHandler); 4] = [
+            ("pointerdown", on_pointer_down),
+            ("pointermove", on_pointer_move),
+            ("pointerup", on_pointer_up),
+            ("pointercancel", on_pointer_up),
+        ];
+        for (event, handler) in handlers.iter() {
+            let shared = interaction.clone();
+            let handler = *handler;
+            let listener = EventListener::new(&node, event, move |event: Event| {
+                if let Ok(event) = event.dyn_into::<PointerEvent>() {
+                    handler(&shared, event);
+                }
+            })?;
+            pane.add_listener(listener);
+        }
+        pane.set_interaction(interaction.clone());
+        Ok(interaction)
+    }
+}
+
+fn on_pointer_down(interaction: &RefCell<Interaction>, event: PointerEvent) {
+    let mut i = interaction.borrow_mut();
+    if i.active.is_some() {
+        return;
+    }
+    let node = match event
+        .current_target()
+        .and_then(|t| t.dyn_into::<Element>().ok())
+    {
+        Some(node) => node,
+        None => return,
+    };
+    if from_child_pane(&event, &node) {
+        return;
+    }
+    let (x, y) = (event.client_x(), event.client_y());
+    let edges = match &i.resize {
+        Some(resize) => resize.edges.hit(&node.get_bounding_client_rect(), x, y),
+        None => Edges::NONE,
+    };
+    let kind = if edges.any() {
+        DragKind::Resize
+    } else if i
+        .drag
+        .as_ref()
+        .map(|drag| drag.matches_handle(&node, &event))
+        .unwrap_or(false)
+    {
+        DragKind::Move
+    } else {
+        return;
+    };
+    let div = i.div;
+    let start = state::exec(|state| {
+        let pane = state.nodes.get(&div)?;
+        Ok((pane.rect(), state.pane_scale(pane)?, state.placement(pane)?))
+    });
+    let (start_rect, scale, placement) = match start {
+        Ok(start) => start,
+        Err(_) => return,
+    };
+    i.active = Some(ActiveDrag {
+        kind,
+        edges,
+        start: (x, y),
+        start_rect,
+        scale,
+        placement,
+    });
+    let _ = node.set_pointer_capture(event.pointer_id());
+    event.prevent_default();
+    let callback = i.callback.clone();
+    drop(i);
+    notify(callback, kind, DragPhase::Start, start_rect);
+}
+
+fn on_pointer_move(interaction: &RefCell<Interaction>, event: PointerEvent) {
+    let (x, y) = (event.client_x(), event.client_y());
+    if interaction.borrow().active.is_none() {
+        // Hovering, show where the div can be resized
+        if let Some(node) = event
+            .current_target()
+            .and_then(|t| t.dyn_into::<Element>().ok())
+        {
+            interaction.borrow_mut().show_resize_cursor(&node, &event);
+        }
+        return;
+    }
+    let i = interaction.borrow();
+    let active = match &i.active {
+        Some(active) => active,
+        None => return,
+    };
+    let dx = ((x - active.start.0) as f32 / active.scale.0) as i32;
+    let dy = ((y - active.start.1) as f32 / active.scale.1) as i32;
+    let start = active.placement.frame_rect(active.start_rect);
+    let rect = match active.kind {
+        DragKind::Move => i
+            .drag
+            .as_ref()
+            .map(|drag| drag.moved(start, dx, dy))
+            .unwrap_or(start),
+        DragKind::Resize => i
+            .resize
+            .as_ref()
+            .map(|resize| resize.resized(start, active.edges, dx, dy))
+            .unwrap_or(start),
+    };
+    let rect = active.placement.pane_rect(rect);
+    let div = i.div;
+    let result = state::exec_mut(|state| {
+        state.update_pane(&div, Some(rect.x), Some(rect.y), Some(rect.w), Some(rect.h))
+    });
+    if result.is_err() {
+        return;
+    }
+    let (kind, callback) = (active.kind, i.callback.clone());
+    drop(i);
+    notify(callback, kind, DragPhase::Move, rect);
+}
+
+fn on_pointer_up(interaction: &RefCell<Interaction>, event: PointerEvent) {
+    let mut i = interaction.borrow_mut();
+    let active = match i.active.take() {
+        Some(active) => active,
+        None => return,
+    };
+    if let Some(node) = event
+        .current_target()
+        .and_then(|t| t.dyn_into::<Element>().ok())
+    {
+        let _ = node.release_pointer_capture(event.pointer_id());
+    }
+    let div = i.div;
+    let rect = state::exec(|state| Ok(state.nodes.get(&div)?.rect())).unwrap_or(active.start_rect);
+    let callback = i.callback.clone();
+    drop(i);
+    notify(callback, active.kind, DragPhase::End, rect);
+}
+
+/// Whether the event originates from a child pane mounted inside of the node, which is not part of the node's interactions
+fn from_child_pane(event: &Event, node: &Element) -> bool {
+    for target in event.composed_path().iter() {
+        let element = match target.dyn_into::<Element>() {
+            Ok(element) => element,
+            Err(_) => continue,
+        };
+        if element.is_same_node(Some(node)) {
+            return false;
+        }
+        if element.class_list().contains("div-rs") {
+            return true;
+        }
+    }
+    false
+}
+
+fn notify(callback: Option<DragCallback>, kind: DragKind, phase: DragPhase, rect: Rect) {
+    if let Some(callback) = callback {
+        (callback.borrow_mut())(DragEvent { kind, phase, rect });
+    }
+}
+
+impl Interaction {
+    /// Shows a resize cursor while the pointer is close to a resizable edge.
+    /// It is set with a class, such that a cursor set by the user through the CSS functions is kept.
+    fn show_resize_cursor(&mut self, node: &Element, event: &PointerEvent) {
+        let class = match &self.resize {
+            Some(resize) if !from_child_pane(event, node) => resize
+                .edges
+                .hit(
+                    &node.get_bounding_client_rect(),
+                    event.client_x(),
+                    event.client_y(),
+                )
+                .cursor_class(),
+            _ => None,
+        };
+        if class == self.cursor_class {
+            return;
+        }
+        let list = node.class_list();
+        if let Some(old) = self.cursor_class {
+            let _ = list.remove_1(old);
+        }
+        if let Some(new) = class {
+            let _ = list.add_1(new);
+        }
+        self.cursor_class = class;
+    }
+}
+
+impl DragConfig {
+    /// Moves the rectangle covered by the div in the frame
+    /// Without a handle selector, the entire div is the handle
+    fn matches_handle(&self, node: &Element, event: &PointerEvent) -> bool {
+        let selector = match &self.handle_selector {
+            Some(selector) => selector,
+            None => return true,
+        };
+        let target = match event.target().and_then(|t| t.dyn_into::<Element>().ok()) {
+            Some(target) => target,
+            None => return false,
+        };
+        match target.closest(selector) {
+            Ok(Some(handle)) => node.contains(Some(&handle)),
+            _ => false,
+        }
+    }
+    fn moved(&self, start: Rect, dx: i32, dy: i32) -> Rect {
+        let mut rect = Rect::new(start.x + dx, start.y + dy, start.w, start.h);
+        if let Some(bounds) = &self.bounds {
+            rect.x = rect.x.min(bounds.right() - rect.w as i32).max(bounds.x);
+            rect.y = rect.y.min(bounds.bottom() - rect.h as i32).max(bounds.y);
+        }
+        rect
+    }
+}
+
+impl ResizeConfig {
+    /// Resizes the rectangle covered by the div in the frame, keeping the opposite edges in place
+    fn resized(&self, start: Rect, edges: Edges, dx: i32, dy: i32) -> Rect {
+        let (max_w, max_h) = self.max.unwrap_or((u32::MAX, u32::MAX));
+        let clamp_w = |w: i32| (w.max(0) as u32).max(self.min.0).min(max_w);
+        let clamp_h = |h: i32| (h.max(0) as u32).max(self.min.1).min(max_h);
+        let mut rect = start;
+        if edges.right {
+            rect.w = clamp_w(start.w as i32 + dx);
+        }
+        if edges.left {
+            rect.w = clamp_w(start.w as i32 - dx);
+            rect.x = start.right() - rect.w as i32;
+        }
+        if edges.bottom {
+            rect.h = clamp_h(start.h as i32 + dy);
+        }
+        if edges.top {
+            rect.h = clamp_h(start.h as i32 - dy);
+            rect.y = start.bottom() - rect.h as i32;
+        }
+        rect
+    }
+}
+
+impl fmt::Debug for Interaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Interaction")
+            .field("div", &self.div)
+            .field("drag", &self.drag)
+            .field("resize", &self.resize)
+            .field("active", &self.active)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOP_RIGHT: Placement = Placement {
+        anchor: (800.0, 0.0),
+        pivot: (1.0, 0.0),
+    };
+
+    #[test]
+    fn converts_positions_to_the_frame() {
+        let top_left = Placement {
+            anchor: (0.0, 0.0),
+            pivot: (0.0, 0.0),
+        };
+        let rect = Rect::new(10, 20, 100, 50);
+        assert_eq!(top_left.frame_rect(rect), rect);
+        // 10 from the right border of an 800 wide frame
+        let rect = Rect::new(-10, 20, 100, 50);
+        assert_eq!(TOP_RIGHT.frame_rect(rect), Rect::new(690, 20, 100, 50));
+        assert_eq!(TOP_RIGHT.pane_rect(TOP_RIGHT.frame_rect(rect)), rect);
+        let center = Placement {
+            anchor: (400.0, 300.0),
+            pivot: (0.5, 0.5),
+        };
+        assert_eq!(
+            center.frame_rect(Rect::new(0, 0, 100, 50)),
+            Rect::new(350, 275, 100, 50)
+        );
+    }
+
+    #[test]
+    fn resizes_anchored_divs_at_the_dragged_edge() {
+        let resize = ResizeConfig {
+            edges: Edges::ALL,
+            min: (20, 20),
+            max: None,
+        };
+        let rect = Rect::new(-10, 20, 100, 50);
+        let right = Edges {
+            right: true,
+            ..Edges::NONE
+        };
+        let resized = resize.resized(TOP_RIGHT.frame_rect(rect), right, 30, 0);
+        assert_eq!(resized, Rect::new(690, 20, 130, 50));
+        // The right edge moves right, so the distance to the right border of the frame shrinks
+        assert_eq!(TOP_RIGHT.pane_rect(resized), Rect::new(20, 20, 130, 50));
+        let left = Edges {
+            left: true,
+            ..Edges::NONE
+        };
+        let resized = resize.resized(TOP_RIGHT.frame_rect(rect), left, 30, 0);
+        assert_eq!(TOP_RIGHT.pane_rect(resized), Rect::new(-10, 20, 70, 50));
+    }
+
+    #[test]
+    fn keeps_dragged_divs_in_bounds() {
+        let drag = DragConfig {
+            handle_selector: None,
+            bounds: Some(Rect::new(0, 0, 800, 600)),
+        };
+        let rect = Rect::new(-10, 20, 100, 50);
+        let moved = drag.moved(TOP_RIGHT.frame_rect(rect), 50, -50);
+        assert_eq!(moved, Rect::new(700, 0, 100, 50));
+        assert_eq!(TOP_RIGHT.pane_rect(moved), Rect::new(0, 0, 100, 50));
+    }
+}
@@ -0,0 +1,486 @@
+//! Layout containers that compute the geometry of a set of divs
+//!
+//! Containers own a list of DivHandles and an area in frame coordinates.
+//! Whenever the container is moved or resized, the position and size of all divs in it are updated.
+//! The divs should not be in a group and use the default anchor, otherwise their positions are not interpreted as frame coordinates.
+
+use crate::events::EventListener;
+use crate::state;
+use crate::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, PointerEvent};
+
+/// Main axis of a stack or a split view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// Places divs one after another along the main axis, with a fixed spacing between them.
+///
+/// Each div keeps its own size along the main axis and is stretched to the container size along the other axis.
+/// # Example
+/// ```no_run
+/// use div::{Direction, Rect, StackLayout};
+/// let mut toolbar = StackLayout::new(Rect::new(0, 0, 800, 40), Direction::Horizontal, 5);
+/// toolbar.add(div::new(0, 0, 100, 40, "File").unwrap()).unwrap();
+/// toolbar.add(div::new(0, 0, 100, 40, "Edit").unwrap()).unwrap();
+/// toolbar.reposition(0, 600).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct StackLayout {
+    area: Rect,
+    direction: Direction,
+    spacing: u32,
+    divs: Vec<DivHandle>,
+}
+
+/// Places divs in a grid of cells with equal size, filling it row by row.
+#[derive(Debug, Clone)]
+pub struct GridLayout {
+    area: Rect,
+    rows: u32,
+    cols: u32,
+    spacing: u32,
+    divs: Vec<DivHandle>,
+}
+
+/// Splits an area between two divs, with a divider in between that can be dragged by the user.
+///
+/// The divider is a div itself, with the CSS class `div-rs-divider`. It is deleted when the split view is dropped.
+pub struct SplitView {
+    inner: Rc<RefCell<SplitState>>,
+    divider: DivHandle,
+    /// Pointer events on the divider, removed when the split view is dropped
+    _listeners: Vec<EventListener>,
+}
+
+#[derive(Debug)]
+struct SplitState {
+    area: Rect,
+    direction: Direction,
+    first: DivHandle,
+    second: DivHandle,
+    divider: DivHandle,
+    divider_width: u32,
+    ratio: f32,
+    dragging: bool,
+}
+
+impl StackLayout {
+    pub fn new(area: Rect, direction: Direction, spacing: u32) -> Self {
+        StackLayout {
+            area,
+            direction,
+            spacing,
+            divs: vec![],
+        }
+    }
+    /// Appends a div at the end of the stack
+    pub fn add(&mut self, div: DivHandle) -> Result<(), DivError> {
+        self.divs.push(div);
+        self.relayout()
+    }
+    /// Removes a div from the stack, the remaining divs are moved to close the gap.
+    /// The div itself is not deleted.
+    pub fn remove(&mut self, div: DivHandle) -> Result<(), DivError> {
+        self.divs.retain(|d| *d != div);
+        self.relayout()
+    }
+    pub fn divs(&self) -> &[DivHandle] {
+        &self.divs
+    }
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+    pub fn reposition(&mut self, x: i32, y: i32) -> Result<(), DivError> {
+        self.area.x = x;
+        self.area.y = y;
+        self.relayout()
+    }
+    pub fn resize(&mut self, w: u32, h: u32) -> Result<(), DivError> {
+        self.area.w = w;
+        self.area.h = h;
+        self.relayout()
+    }
+    pub fn set_spacing(&mut self, spacing: u32) -> Result<(), DivError> {
+        self.spacing = spacing;
+        self.relayout()
+    }
+    /// Recomputes the geometry of all divs in the stack, e.g. after one of them has been resized.
+    pub fn relayout(&self) -> Result<(), DivError> {
+        state::exec_mut(|state| {
+            let sizes = self
+                .divs
+                .iter()
+                .map(|div| {
+                    let rect = state.nodes.get(div)?.rect();
+                    Ok((rect.w, rect.h))
+                })
+                .collect::<Result<Vec<_>, DivError>>()?;
+            for (div, rect) in self.divs.iter().zip(self.rects(&sizes)) {
+                state.update_pane(div, Some(rect.x), Some(rect.y), Some(rect.w), Some(rect.h))?;
+            }
+            Ok(())
+        })
+    }
+    /// The geometry of divs with the given sizes, placed in the stack
+    fn rects(&self, sizes: &[(u32, u32)]) -> Vec<Rect> {
+        let mut offset = 0;
+        sizes
+            .iter()
+            .map(|(w, h)| match self.direction {
+                Direction::Horizontal => {
+                    let rect = Rect::new(self.area.x + offset, self.area.y, *w, self.area.h);
+                    offset += (w + self.spacing) as i32;
+                    rect
+                }
+                Direction::Vertical => {
+                    let rect = Rect::new(self.area.x, self.area.y + offset, self.area.w, *h);
+                    offset += (h + self.spacing) as i32;
+                    rect
+                }
+            })
+            .collect()
+    }
+}
+
+impl GridLayout {
+    pub fn new(area: Rect, rows: u32, cols: u32, spacing: u32) -> Self {
+        GridLayout {
+            area,
+            rows,
+            cols,
+            spacing,
+            divs: vec![],
+        }
+    }
+    /// Puts the div into the next free cell. Divs that do not fit into the grid are not moved.
+    pub fn add(&mut self, div: DivHandle) -> Result<(), DivError> {
+        self.divs.push(div);
+        self.relayout()
+    }
+    /// Removes a div from the grid, the following divs move up by one cell.
+    /// The div itself is not deleted.
+    pub fn remove(&mut self, div: DivHandle) -> Result<(), DivError> {
+        self.divs.retain(|d| *d != div);
+        self.relayout()
+    }
+    pub fn divs(&self) -> &[DivHandle] {
+        &self.divs
+    }
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+    pub fn reposition(&mut self, x: i32, y: i32) -> Result<(), DivError> {
+        self.area.x = x;
+        self.area.y = y;
+        self.relayout()
+    }
+    pub fn resize(&mut self, w: u32, h: u32) -> Result<(), DivError> {
+        self.area.w = w;
+        self.area.h = h;
+        self.relayout()
+    }
+    /// Changes the number of rows and columns
+    pub fn set_cells(&mut self, rows: u32, cols: u32) -> Result<(), DivError> {
+        self.rows = rows;
+        self.cols = cols;
+        self.relayout()
+    }
+    pub fn set_spacing(&mut self, spacing: u32) -> Result<(), DivError> {
+        self.spacing = spacing;
+        self.relayout()
+    }
+    /// The area covered by a cell, in frame coordinates
+    pub fn cell(&self, row: u32, col: u32) -> Rect {
+        let cell_w = cell_size(self.area.w, self.cols, self.spacing);
+        let cell_h = cell_size(self.area.h, self.rows, self.spacing);
+        Rect::new(
+            self.area.x + (col * (cell_w + self.spacing)) as i32,
+            self.area.y + (row * (cell_h + self.spacing)) as i32,
+            cell_w,
+            cell_h,
+        )
+    }
+    /// Recomputes the geometry of all divs in the grid
+    pub fn relayout(&self) -> Result<(), DivError> {
+        let cells = (self.rows * self.cols) as usize;
+        state::exec_mut(|state| {
+            for (i, div) in self.divs.iter().enumerate().take(cells) {
+                let i = i as u32;
+                let cell = self.cell(i / self.cols, i % self.cols);
+                state.update_pane(div, Some(cell.x), Some(cell.y), Some(cell.w), Some(cell.h))?;
+            }
+            Ok(())
+        })
+    }
+}
+
+fn cell_size(total: u32, n: u32, spacing: u32) -> u32 {
+    if n == 0 {
+        return 0;
+    }
+    total.saturating_sub((n - 1) * spacing) / n
+}
+
+impl SplitView {
+    /// Creates a split view with the divider in the middle of the area and registers the pointer events on the divider.
+    pub fn new(
+        area: Rect,
+        direction: Direction,
+        first: DivHandle,
+        second: DivHandle,
+    ) -> Result<SplitView, DivError> {
+        let cursor = match direction {
+            Direction::Horizontal => "col-resize",
+            Direction::Vertical => "row-resize",
+        };
+        let divider = builder()
+            .class("div-rs-divider")
+            .css("cursor", cursor)
+            .css("touch-action", "none")
+            .build()?;
+        let inner = Rc::new(RefCell::new(SplitState {
+            area,
+            direction,
+            first,
+            second,
+            divider,
+            divider_width: 6,
+            ratio: 0.5,
+            dragging: false,
+        }));
+        inner.borrow().relayout()?;
+
+        let node = divider.parent_element()?;
+        let mut listeners = vec![];
+
+        let state = inner.clone();
+        let target = node.clone();
+        listeners.push(EventListener::new(
+            &node,
+            "pointerdown",
+            move |event: Event| {
+                if let Ok(event) = event.dyn_into::<PointerEvent>() {
+                    state.borrow_mut().dragging = true;
+                    let _ = target.set_pointer_capture(event.pointer_id());
+                    event.prevent_default();
+                }
+            },
+        )?);
+
+        let state = inner.clone();
+        listeners.push(EventListener::new(
+            &node,
+            "pointermove",
+            move |event: Event| {
+                let mut state = state.borrow_mut();
+                if let (true, Ok(event)) = (state.dragging, event.dyn_into::<PointerEvent>()) {
+                    if let Ok(pos) = client_to_frame(event.client_x(), event.client_y()) {
+                        state.drag_to(pos);
+                        let _ = state.relayout();
+                    }
+                }
+            },
+        )?);
+
+        for event in &["pointerup", "pointercancel"] {
+            let state = inner.clone();
+            listeners.push(EventListener::new(&node, event, move |_: Event| {
+                state.borrow_mut().dragging = false;
+            })?);
+        }
+
+        Ok(SplitView {
+            inner,
+            divider,
+            _listeners: listeners,
+        })
+    }
+    /// The share of the area given to the first div, between 0.0 and 1.0
+    pub fn ratio(&self) -> f32 {
+        self.inner.borrow().ratio
+    }
+    pub fn set_ratio(&self, ratio: f32) -> Result<(), DivError> {
+        let mut state = self.inner.borrow_mut();
+        state.ratio = ratio.clamp(0.0, 1.0);
+        state.relayout()
+    }
+    /// Thickness of the divider in frame units, 6 by default
+    pub fn set_divider_width(&self, width: u32) -> Result<(), DivError> {
+        let mut state = self.inner.borrow_mut();
+        state.divider_width = width;
+        state.relayout()
+    }
+    /// The div used as divider, e.g. to add CSS classes to it
+    pub fn divider(&self) -> DivHandle {
+        self.divider
+    }
+    pub fn area(&self) -> Rect {
+        self.inner.borrow().area
+    }
+    pub fn reposition(&self, x: i32, y: i32) -> Result<(), DivError> {
+        let mut state = self.inner.borrow_mut();
+        state.area.x = x;
+        state.area.y = y;
+        state.relayout()
+    }
+    pub fn resize(&self, w: u32, h: u32) -> Result<(), DivError> {
+        let mut state = self.inner.borrow_mut();
+        state.area.w = w;
+        state.area.h = h;
+        state.relayout()
+    }
+}
+
+impl Drop for SplitView {
+    fn drop(&mut self) {
+        let _ = self.divider.delete();
+    }
+}
+
+impl SplitState {
+    /// Moves the divider to the given point in frame coordinates
+    fn drag_to(&mut self, (x, y): (i32, i32)) {
+        let (pos, start, len) = match self.direction {
+            Direction::Horizontal => (x, self.area.x, self.area.w),
+            Direction::Vertical => (y, self.area.y, self.area.h),
+        };
+        let available = len.saturating_sub(self.divider_width);
+        if available > 0 {
+            let first = pos - start - self.divider_width as i32 / 2;
+            self.ratio = (first as f32 / available as f32).clamp(0.0, 1.0);
+        }
+    }
+    fn relayout(&self) -> Result<(), DivError> {
+        let [first, divider, second] = self.rects();
+        state::exec_mut(|state| {
+            state.update_pane(
+                &self.first,
+                Some(first.x),
+                Some(first.y),
+                Some(first.w),
+                Some(first.h),
+            )?;
+            state.update_pane(
+                &self.divider,
+                Some(divider.x),
+                Some(divider.y),
+                Some(divider.w),
+                Some(divider.h),
+            )?;
+            state.update_pane(
+                &self.second,
+                Some(second.x),
+                Some(second.y),
+                Some(second.w),
+                Some(second.h),
+            )
+        })
+    }
+    /// The geometry of the first div, the divider and the second div
+    fn rects(&self) -> [Rect; 3] {
+        let Rect { x, y, w, h } = self.area;
+        let d = self.divider_width;
+        match self.direction {
+            Direction::Horizontal => {
+                let first = (w.saturating_sub(d) as f32 * self.ratio) as u32;
+                let second = w.saturating_sub(d + first);
+                let x1 = x + first as i32;
+                let x2 = x1 + d as i32;
+                [
+                    Rect::new(x, y, first, h),
+                    Rect::new(x1, y, d, h),
+                    Rect::new(x2, y, second, h),
+                ]
+            }
+            Direction::Vertical => {
+                let first = (h.saturating_sub(d) as f32 * self.ratio) as u32;
+                let second = h.saturating_sub(d + first);
+                let y1 = y + first as i32;
+                let y2 = y1 + d as i32;
+                [
+                    Rect::new(x, y, w, first),
+                    Rect::new(x, y1, w, d),
+                    Rect::new(x, y2, w, second),
+                ]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_places_divs_along_the_main_axis() {
+        let stack = StackLayout::new(Rect::new(10, 20, 300, 40), Direction::Horizontal, 5);
+        assert_eq!(
+            stack.rects(&[(100, 10), (50, 80)]),
+            vec![Rect::new(10, 20, 100, 40), Rect::new(115, 20, 50, 40)]
+        );
+        let stack = StackLayout::new(Rect::new(0, 0, 200, 500), Direction::Vertical, 0);
+        assert_eq!(
+            stack.rects(&[(10, 30), (10, 70)]),
+            vec![Rect::new(0, 0, 200, 30), Rect::new(0, 30, 200, 70)]
+        );
+    }
+
+    #[test]
+    fn grid_cells_share_the_area() {
+        let grid = GridLayout::new(Rect::new(100, 100, 310, 210), 2, 3, 5);
+        assert_eq!(grid.cell(0, 0), Rect::new(100, 100, 100, 102));
+        assert_eq!(grid.cell(1, 2), Rect::new(310, 207, 100, 102));
+        assert_eq!(cell_size(100, 0, 5), 0);
+        assert_eq!(cell_size(10, 3, 10), 0);
+    }
+
+    fn split(direction: Direction, ratio: f32) -> SplitState {
+        SplitState {
+            area: Rect::new(0, 0, 206, 106),
+            direction,
+            first: DivHandle(0),
+            second: DivHandle(1),
+            divider: DivHandle(2),
+            divider_width: 6,
+            ratio,
+            dragging: false,
+        }
+    }
+
+    #[test]
+    fn split_view_divides_the_area() {
+        assert_eq!(
+            split(Direction::Horizontal, 0.25).rects(),
+            [
+                Rect::new(0, 0, 50, 106),
+                Rect::new(50, 0, 6, 106),
+                Rect::new(56, 0, 150, 106)
+            ]
+        );
+        assert_eq!(
+            split(Direction::Vertical, 1.0).rects(),
+            [
+                Rect::new(0, 0, 206, 100),
+                Rect::new(0, 100, 206, 6),
+                Rect::new(0, 106, 206, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn split_view_divider_follows_the_pointer() {
+        let mut state = split(Direction::Horizontal, 0.5);
+        state.drag_to((53, 0));
+        assert_eq!(state.ratio, 0.25);
+        state.drag_to((-100, 0));
+        assert_eq!(state.ratio, 0.0);
+        state.drag_to((1000, 0));
+        assert_eq!(state.ratio, 1.0);
+    }
+}
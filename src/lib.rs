@@ -1,23 +1,59 @@
 use std::{future::Future, sync::RwLock};
 use web_sys::Element;
 
+mod animation;
+mod binding;
+pub mod builder;
+mod camera;
 mod class;
+pub mod css;
 pub mod div_handle;
 pub mod error;
+mod events;
+mod geometry;
 pub mod global;
+pub mod group;
+mod hit_test;
+mod html;
+mod input;
+mod interaction;
+pub mod layout;
+mod owned;
 mod pane;
+#[cfg(feature = "serde")]
+mod scene;
+mod signal;
+mod snapshot;
 mod state;
 mod storage;
 mod style;
 mod utils;
 
+pub use animation::{AnimationResult, Easing, Effect, Transition};
+pub use binding::*;
+pub use builder::*;
+pub use camera::*;
 pub use class::*;
+pub use css::*;
 pub use div_handle::*;
 pub use error::*;
+pub use geometry::*;
 pub use global::*;
+pub use group::*;
+pub use hit_test::*;
+pub use html::*;
+pub use input::InputHandle;
+pub use interaction::{DragEvent, DragKind, DragPhase, Edges};
+pub use layout::*;
+pub use owned::OwnedDiv;
+#[cfg(feature = "serde")]
+pub use scene::*;
+pub use signal::Signal;
+pub use snapshot::*;
 use state::*;
 use storage::{ClassStorage, PaneHashMap, PaneStorage};
 use style::*;
+pub use style::{HideStrategy, ShadowMode};
 pub use utils::doc;
 
 /// Mounts the div to the HTML body
@@ -34,7 +70,7 @@ pub fn init_to(id: &str) -> Result<(), DivError> {
 /// Mounts a global div as a child of the element provided.
 /// The specified dimensions restrict the area in which divs are visible.
 /// # Example
-/// ```
+/// ```no_run
 /// let width = 1280;
 /// let height = 720;
 /// let root = web_sys::window().unwrap().document().unwrap().get_element_by_id("my-root-id").unwrap();
@@ -48,9 +84,11 @@ pub fn init_ex_with_element(
     state::set_state(GlobalState {
         root,
         nodes: PaneHashMap::default(),
+        groups: GroupStorage::default(),
         pos,
         size,
         zoom: (1.0, 1.0),
+        hide_strategy: HideStrategy::default(),
         classes: JsClassStorage::default(),
     })?;
     add_div_styles_to_document()?;
@@ -62,7 +100,7 @@ pub fn init_ex_with_element(
 /// Mounts a global div as a child of the HTML element with the defined ID.
 /// The specified dimensions restrict the area in which divs are visible.
 /// # Example
-/// ```
+/// ```no_run
 /// let width = 1280;
 /// let height = 720;
 /// div::init_ex(Some("div-root"), (0, 0), Some((width, height)));
@@ -77,10 +115,10 @@ pub fn init_ex(
 }
 
 fn get_root(id: Option<&str>) -> Result<Element, DivError> {
-    let element = if id.is_some() {
+    let element = if let Some(id) = id {
         doc()?
-            .get_element_by_id(id.unwrap())
-            .ok_or(DivError::MissingRoot(id.unwrap().to_owned()))?
+            .get_element_by_id(id)
+            .ok_or_else(|| DivError::MissingRoot(id.to_owned()))?
     } else {
         doc()?.body().ok_or(DivError::MissingBody)?.into()
     };
@@ -89,10 +127,10 @@ fn get_root(id: Option<&str>) -> Result<Element, DivError> {
 
 /// Creates a new div at the defined position with the given HTML as content.
 /// Use the returned DivHandle to manipulate the div.
+///
+/// The HTML is inserted as it is. Use the `html!` macro to build it with escaped values.
 pub fn new(x: i32, y: i32, w: u32, h: u32, html: &str) -> Result<DivHandle, DivError> {
-    let css = "";
-    let classes = "";
-    state::exec_mut(|state| state.new_pane(x, y, w, h, html, css, classes))
+    builder().pos(x, y).size(w, h).html(html).build()
 }
 
 /// Creates a new div at the defined position with the given HTML as content and with CSS classes and inline styles.
@@ -103,9 +141,12 @@ pub fn new(x: i32, y: i32, w: u32, h: u32, html: &str) -> Result<DivHandle, DivE
 ///
 /// This function has several generic parameters to maximize flexibility and allow for all combinations of &str and String.
 /// When using empty iterators, sometimes the compiler gets irritated.
-/// Use explicit type to help it.
+/// Use explicit type to help it, or use `div::builder()` instead.
+/// Instead of property and value pairs, a reference to a typed `Style` can be passed as well.
+/// The properties protected in `DivHandle::set_css` are rejected with `DivError::ReservedProperty`,
+/// classes used by div internally with `DivError::ReservedClass`.
 /// # Example
-/// ```
+/// ```no_run
 /// let html = "Some text";
 /// let classes = ["my-class"];
 /// let css: [(&str, &str);0] = [];
@@ -132,19 +173,34 @@ where
     S2: AsRef<str> + 'a,
     S3: AsRef<str> + 'a,
 {
-    let css_str = css
-        .into_iter()
-        .map(|(attr, val)| attr.as_ref().to_owned() + ": " + val.as_ref() + ";")
-        .collect::<Vec<_>>()
-        .join(" ");
-
-    let classes_str = classes
-        .into_iter()
-        .map(AsRef::as_ref)
-        .collect::<Vec<_>>()
-        .join(" ");
+    let mut builder = builder()
+        .pos(x, y)
+        .size(w, h)
+        .html(html)
+        .classes(classes.into_iter().map(AsRef::as_ref));
+    for (attr, val) in css {
+        builder = builder.css(attr.as_ref(), val.as_ref());
+    }
+    builder.build()
+}
 
-    state::exec_mut(|state| state.new_pane(x, y, w, h, html, &classes_str, &css_str))
+/// Creates a new div at the defined position with the given HTML as content and a style-sheet that only applies inside of it.
+///
+/// See `DivHandle::add_scoped_css` for how the style-sheet is scoped.
+pub fn new_with_css(
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    html: &str,
+    css: &str,
+) -> Result<DivHandle, DivError> {
+    builder()
+        .pos(x, y)
+        .size(w, h)
+        .html(html)
+        .scoped_css(css)
+        .build()
 }
 
 /// **Experimental: This API is experimental and my not be included in later versions**
@@ -154,7 +210,7 @@ where
 /// That future will have to be handled in one way or another.
 /// The most direct way would be to use `wasm_bindgen_futures::spawn_local`
 /// ## Example
-/// ```rust
+/// ```no_run
 /// const X: i32 = 100;
 /// const Y: i32 = 100;
 /// const W: u32 = 500;
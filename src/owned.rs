@@ -0,0 +1,63 @@
+//! Divs that are deleted automatically, following the usual ownership rules of Rust
+
+use crate::*;
+use std::ops::Deref;
+
+/// Owns a div and deletes it when dropped.
+///
+/// All operations of `DivHandle` are available through `Deref`.
+/// Copies of the handle obtained through it become invalid once the `OwnedDiv` is dropped.
+/// # Example
+/// ```no_run
+/// {
+///     let div = div::builder().size(100, 100).html("Temporary").build_owned().unwrap();
+///     div.set_css("color", "red").unwrap();
+/// } // The div is removed from the DOM here
+///
+/// // Keep a div alive forever
+/// let handle = div::OwnedDiv::from(div::new(0, 0, 100, 100, "Permanent").unwrap()).into_handle();
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OwnedDiv {
+    handle: DivHandle,
+}
+
+impl OwnedDiv {
+    /// The handle of the owned div. It must not be used after the `OwnedDiv` has been dropped.
+    pub fn handle(&self) -> DivHandle {
+        self.handle
+    }
+    /// Gives up ownership without deleting the div, which then has to be deleted manually, if at all.
+    pub fn into_handle(self) -> DivHandle {
+        let handle = self.handle;
+        std::mem::forget(self);
+        handle
+    }
+}
+
+impl From<DivHandle> for OwnedDiv {
+    fn from(handle: DivHandle) -> Self {
+        OwnedDiv { handle }
+    }
+}
+
+impl Deref for OwnedDiv {
+    type Target = DivHandle;
+    fn deref(&self) -> &DivHandle {
+        &self.handle
+    }
+}
+
+impl Drop for OwnedDiv {
+    fn drop(&mut self) {
+        // The div may have been deleted through a copy of the handle already
+        let _ = self.handle.delete();
+    }
+}
+
+impl DivBuilder {
+    /// Creates the div as configured, owned by the returned value. See `OwnedDiv`.
+    pub fn build_owned(&self) -> Result<OwnedDiv, DivError> {
+        self.build().map(OwnedDiv::from)
+    }
+}
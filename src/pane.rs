@@ -1,7 +1,14 @@
+use crate::animation::ActiveAnimation;
+use crate::events::EventListener;
+use crate::interaction::Interaction;
+use crate::signal::SignalBinding;
 use crate::storage::PaneStorage;
 use crate::*;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlElement;
+use web_sys::{Element, HtmlElement, Node, NodeList, ShadowRoot, ShadowRootInit};
 
 /// Internal representation of the state required to control a div in the browser.
 ///
@@ -12,83 +19,273 @@ use web_sys::HtmlElement;
 #[derive(Debug)]
 pub(crate) struct Pane {
     node: HtmlElement,
+    /// The node this pane is attached to while displayed, either the root element or the content of the parent pane
+    mount: Node,
+    parent: Option<DivHandle>,
+    group: Option<DivGroup>,
+    /// Whether the pane should be displayed, as requested through hide / show
     displayed: bool,
+    /// How the pane is currently hidden, None if it is visible. This also depends on the group visibility.
+    hidden_with: Option<HideStrategy>,
+    /// Overrides the global hide strategy for this pane
+    hide_strategy: Option<HideStrategy>,
+    /// HTML attributes set on the pane node through the div API
+    attributes: BTreeMap<String, String>,
     x: i32,
     y: i32,
     w: u32,
     h: u32,
+    /// z-index, None leaves it at the default defined in the div-rs stylesheet
+    z: Option<i32>,
+    /// Width and height follow the content instead of w and h
+    auto_size: (bool, bool),
+    min_size: Option<(u32, u32)>,
+    max_size: Option<(u32, u32)>,
+    anchor: Anchor,
+    /// Point of the pane that is placed at its position, as fractions of its size. Defaults to the anchor fraction.
+    pivot: Option<(f32, f32)>,
+    interaction: Option<Rc<RefCell<Interaction>>>,
+    /// Event listeners registered on the node, removed when the pane is dropped
+    listeners: Vec<EventListener>,
+    /// Event listeners registered on nodes in the content, dropped when their node is replaced
+    content_listeners: Vec<EventListener>,
+    animation: Option<ActiveAnimation>,
+    /// Style element holding the scoped CSS of this pane, in the document head or in the shadow root
+    scoped_style: Option<Element>,
+    /// The scoped CSS as it has been added, before scoping it
+    scoped_css: Vec<String>,
+    /// Shadow root attached to the node, which then holds the content instead of the node itself
+    shadow: Option<ShadowRoot>,
+    /// Nodes in the content with a `data-div-bind` attribute, by its value. Collected whenever the content is replaced.
+    bindings: HashMap<String, Vec<Element>>,
+    /// Bindings to the signals that drive the pane, dropped with the pane
+    signal_bindings: Vec<Rc<SignalBinding>>,
+}
+
+/// Anchor point and pivot of a pane, in the unscaled units of its position. See `GlobalState::placement`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Placement {
+    /// The anchor point, measured from the top-left corner of the frame
+    pub(crate) anchor: (f32, f32),
+    /// Fractions of the size of the pane
+    pub(crate) pivot: (f32, f32),
+}
+
+impl Placement {
+    /// Converts the position and size of a pane to the rectangle it covers in the frame
+    pub(crate) fn frame_rect(&self, rect: Rect) -> Rect {
+        Rect::new(
+            (self.anchor.0 + rect.x as f32 - self.pivot.0 * rect.w as f32).round() as i32,
+            (self.anchor.1 + rect.y as f32 - self.pivot.1 * rect.h as f32).round() as i32,
+            rect.w,
+            rect.h,
+        )
+    }
+    /// Converts a rectangle in the frame back to the position and size of a pane
+    pub(crate) fn pane_rect(&self, rect: Rect) -> Rect {
+        Rect::new(
+            (rect.x as f32 - self.anchor.0 + self.pivot.0 * rect.w as f32).round() as i32,
+            (rect.y as f32 - self.anchor.1 + self.pivot.1 * rect.h as f32).round() as i32,
+            rect.w,
+            rect.h,
+        )
+    }
 }
 
 impl<PS: PaneStorage, CS: ClassStorage> GlobalState<PS, CS> {
-    /// Creates a new pane from specified html and shows it
-    pub(crate) fn new_pane(
-        &mut self,
-        x: i32,
-        y: i32,
-        w: u32,
-        h: u32,
-        html: &str,
-        classes: &str,
-        css: &str,
-    ) -> Result<DivHandle, DivError> {
+    /// Creates a new pane as described by the builder and shows it, unless it should be created hidden
+    pub(crate) fn new_pane(&mut self, spec: &DivBuilder) -> Result<DivHandle, DivError> {
         let window = web_sys::window().ok_or(DivError::MissingWindow)?;
         let doc = window.document().ok_or(DivError::MissingDocument)?;
 
-        let x = self.pos.0 + x;
-        let y = self.pos.1 + y;
+        let mount = match spec.parent {
+            Some(parent) => self.nodes.get(&parent)?.content(),
+            None => self.root.clone().into(),
+        };
+
+        for (property, _) in &spec.css {
+            check_css_property(property)?;
+        }
+        // The classes are joined into the class attribute, where whitespace separates several classes
+        for css_class in spec.classes.iter().flat_map(|c| c.split_whitespace()) {
+            check_class_name(css_class)?;
+        }
+        let css = spec
+            .css
+            .iter()
+            .map(|(attr, val)| attr.to_owned() + ": " + val + ";")
+            .collect::<Vec<_>>()
+            .join(" ");
 
         let node: HtmlElement = doc
             .create_element("div")?
             .dyn_into()
             .map_err(|_| DivError::JsCastError)?;
-        node.set_class_name(&("div-rs ".to_owned() + classes));
-        node.set_inner_html(html);
-        node.set_attribute("style", css)?;
+        node.set_class_name(&("div-rs ".to_owned() + &spec.classes.join(" ")));
+        let shadow = match spec.shadow {
+            Some(mode) => {
+                let shadow = node.attach_shadow(&ShadowRootInit::new(mode.to_web_sys()))?;
+                add_div_styles_to_shadow(&shadow)?;
+                shadow.set_inner_html(&spec.html);
+                Some(shadow)
+            }
+            None => {
+                node.set_inner_html(&spec.html);
+                None
+            }
+        };
+        node.set_attribute("style", &css)?;
+        let mut attributes = BTreeMap::new();
+        if let Some(id) = &spec.id {
+            attributes.insert("id".to_owned(), id.clone());
+        }
+        for (name, value) in &spec.attributes {
+            check_attribute_name(name)?;
+            attributes.insert(name.clone(), value.clone());
+        }
+        for (name, value) in &attributes {
+            node.set_attribute(name, value)?;
+        }
 
-        self.root.append_with_node_1(&node.clone().into())?;
-        let vnode = Pane {
+        if let Some(group) = &spec.group {
+            self.groups.get(group)?;
+        }
+        let mut vnode = Pane {
             node,
-            displayed: true,
-            x,
-            y,
-            w,
-            h,
+            mount,
+            parent: spec.parent,
+            group: spec.group,
+            displayed: !spec.hidden,
+            // The node has not been attached to the DOM, yet
+            hidden_with: Some(HideStrategy::Detach),
+            hide_strategy: spec.hide_strategy,
+            attributes,
+            x: spec.x,
+            y: spec.y,
+            w: spec.w,
+            h: spec.h,
+            z: spec.z,
+            auto_size: spec.auto_size,
+            min_size: spec.min_size,
+            max_size: spec.max_size,
+            anchor: spec.anchor,
+            pivot: spec.pivot,
+            interaction: None,
+            listeners: vec![],
+            content_listeners: vec![],
+            animation: None,
+            scoped_style: None,
+            scoped_css: vec![],
+            shadow,
+            bindings: HashMap::new(),
+            signal_bindings: vec![],
         };
-        vnode.redraw(self.pos, self.zoom)?;
+        vnode.apply_z()?;
+        vnode.collect_bindings()?;
 
         let ph = self.nodes.insert(vnode);
+        for css in &spec.scoped_css {
+            self.add_scoped_css(&ph, css)?;
+        }
+        self.redraw_pane(&ph)?;
         Ok(ph)
     }
-    pub(crate) fn hide_pane(&mut self, p: &DivHandle) -> Result<(), DivError> {
-        let v = &self.nodes.get_mut(p)?;
-        if v.displayed {
-            let old_node = self
-                .root
-                .remove_child(&v.node)
-                .map_err(|_e| DivError::MissingChild)?;
-            let entry = self.nodes.get_mut(p).unwrap();
-            entry.node = old_node.dyn_into().map_err(|_| DivError::JsCastError)?;
-            entry.displayed = false;
+    /// Adds CSS rules that only apply inside of the pane.
+    ///
+    /// The rules are scoped with a class unique to the pane, which is added to the node together with the first rules.
+    /// Panes with a shadow root keep the rules inside of it instead, where they are scoped by the browser.
+    pub(crate) fn add_scoped_css(&mut self, p: &DivHandle, css: &str) -> Result<(), DivError> {
+        let scope = format!("div-rs-scope-{}", p.0);
+        let pane = self.nodes.get_mut(p)?;
+        let style = match &pane.scoped_style {
+            Some(style) => style.clone(),
+            None => {
+                let shadow = pane.shadow.as_ref().map(|shadow| shadow.as_ref());
+                let style = add_scoped_style_element(shadow)?;
+                if shadow.is_none() {
+                    pane.node.class_list().add_1(&scope)?;
+                }
+                pane.scoped_style = Some(style.clone());
+                style
+            }
+        };
+        pane.scoped_css.push(css.to_owned());
+        let mut content = style.text_content().unwrap_or_default();
+        if pane.shadow.is_some() {
+            content += &host_css(css);
+        } else {
+            content += &scope_css(css, &format!(".{}", scope));
         }
+        style.set_text_content(Some(&content));
         Ok(())
     }
+    pub(crate) fn hide_pane(&mut self, p: &DivHandle) -> Result<(), DivError> {
+        self.cancel_animation(p)?;
+        self.set_displayed(p, false)
+    }
     pub(crate) fn show_pane(&mut self, p: &DivHandle) -> Result<(), DivError> {
-        let mut v = self.nodes.get_mut(&p)?;
-        if !v.displayed {
-            self.root.append_child(&v.node)?;
-            v.displayed = true;
+        self.cancel_animation(p)?;
+        self.set_displayed(p, true)
+    }
+    pub(crate) fn set_displayed(&mut self, p: &DivHandle, displayed: bool) -> Result<(), DivError> {
+        self.nodes.get_mut(p)?.displayed = displayed;
+        self.refresh_visibility(p)
+    }
+    /// Attaches or detaches the pane node, depending on whether the pane and its group are visible.
+    /// Panes in a group with a culling camera are also hidden when they are outside the frame.
+    pub(crate) fn refresh_visibility(&mut self, p: &DivHandle) -> Result<(), DivError> {
+        let pane = self.nodes.get(p)?;
+        let mut visible = pane.displayed;
+        if let Some(group) = &pane.group {
+            let group = self.groups.get(group)?;
+            visible &= group.visible;
+            if visible && group.camera.map(|c| c.culling).unwrap_or(false) {
+                visible = self.screen_rect(p)?.intersects(&self.frame_rect());
+            }
         }
-        Ok(())
+        let strategy = pane.hide_strategy.unwrap_or(self.hide_strategy);
+        self.nodes.get_mut(p)?.set_shown(visible, strategy)
     }
+    /// Deletes the pane together with all of its child panes, which are mounted inside of it
     pub(crate) fn delete_pane(&mut self, p: &DivHandle) -> Result<(), DivError> {
+        for child in self.child_panes(p) {
+            self.delete_pane(&child)?;
+        }
         // This removes the node from the DOM
-        self.hide_pane(p)?;
+        self.nodes
+            .get_mut(p)?
+            .set_shown(false, HideStrategy::Detach)?;
         // This deletes all references for GC
-        self.nodes.remove(p)?;
+        let pane = self.nodes.remove(p)?;
+        if let Some(style) = pane.scoped_style {
+            style.remove();
+        }
         Ok(())
     }
+    /// Replaces the content of the pane, keeping its child panes
+    pub(crate) fn set_pane_html(&mut self, p: &DivHandle, html: &str) -> Result<(), DivError> {
+        let children = self
+            .child_panes(p)
+            .iter()
+            .map(|child| Ok(self.nodes.get(child)?.node.clone()))
+            .collect::<Result<Vec<_>, DivError>>()?;
+        self.nodes.get_mut(p)?.set_html(html, &children)
+    }
+    /// Handles of all panes created with the given pane as parent
+    pub(crate) fn child_panes(&self, p: &DivHandle) -> Vec<DivHandle> {
+        self.nodes
+            .handles()
+            .into_iter()
+            .filter(|handle| {
+                self.nodes
+                    .get(handle)
+                    .map(|pane| pane.parent == Some(*p))
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
     pub(crate) fn get_node(&self, p: &DivHandle) -> Result<&HtmlElement, DivError> {
-        let v = self.nodes.get(&p)?;
+        let v = self.nodes.get(p)?;
         Ok(&v.node)
     }
     #[inline(always)]
@@ -100,72 +297,613 @@ impl<PS: PaneStorage, CS: ClassStorage> GlobalState<PS, CS> {
         w: Option<u32>,
         h: Option<u32>,
     ) -> Result<(), DivError> {
-        let mut v = self.nodes.get_mut(&pane_handle)?;
+        self.cancel_animation(pane_handle)?;
+        let v = self.nodes.get_mut(pane_handle)?;
         v.x = x.unwrap_or(v.x);
         v.y = y.unwrap_or(v.y);
         v.w = w.unwrap_or(v.w);
         v.h = h.unwrap_or(v.h);
-        v.redraw(self.pos, self.zoom)?;
-        Ok(())
+        self.redraw_pane(pane_handle)
     }
     pub(crate) fn global_reposition(&mut self, x: i32, y: i32) -> Result<(), DivError> {
         self.pos = (x, y);
-        self.nodes.for_each(&|pane: &mut Pane| {
-            let el = pane.get_element()?;
-            let x = x + pane.x;
-            let y = y + pane.y;
-            let style = el.style();
-            style.set_property("left", &format!("{}px", x))?;
-            style.set_property("top", &format!("{}px", y))?;
-            // js! { @(no_return)
-            //     @{&el}.style.left=@{x} + "px";
-            //     @{&el}.style.top=@{y} + "px";
-            // };
-            Ok(())
-        })
+        self.redraw_all()
     }
     pub(crate) fn global_resize(&mut self, w: u32, h: u32) -> Result<(), DivError> {
         if let Some((width, height)) = self.size {
             let fx = w as f32 / width as f32;
             let fy = h as f32 / height as f32;
             self.zoom = (fx, fy);
-            let zoom = self.zoom;
-            let pos = self.pos;
-            self.nodes.for_each(&|p| p.redraw(pos, zoom))
+            self.redraw_all()
         } else {
             Err(DivError::UndefinedSize)
         }
     }
+    /// Size of the global frame as currently displayed, after all calls to resize
+    pub(crate) fn frame_size(&self) -> Option<(u32, u32)> {
+        self.size.map(|(w, h)| {
+            (
+                (w as f32 * self.zoom.0).round() as u32,
+                (h as f32 * self.zoom.1).round() as u32,
+            )
+        })
+    }
+    /// Updates the pane node to the current geometry of the pane and everything it depends on.
+    /// Child panes are laid out relative to the pane and are redrawn as well.
+    pub(crate) fn redraw_pane(&mut self, p: &DivHandle) -> Result<(), DivError> {
+        // Visibility first, auto-sized panes can only be measured while attached
+        self.refresh_visibility(p)?;
+        let pane = self.nodes.get(p)?;
+        let scale = self.pane_scale(pane)?;
+        // The position depends on the measured size of auto-sized panes, which must be laid out by the browser first
+        pane.apply_auto_size(scale)?;
+        pane.redraw(self.local_screen_rect(pane)?)?;
+        for child in self.child_panes(p) {
+            self.redraw_pane(&child)?;
+        }
+        Ok(())
+    }
+    pub(crate) fn redraw_all(&mut self) -> Result<(), DivError> {
+        for handle in self.nodes.handles() {
+            // Child panes are redrawn together with their parent
+            if self.nodes.get(&handle)?.parent.is_none() {
+                self.redraw_pane(&handle)?;
+            }
+        }
+        Ok(())
+    }
+    /// Position and size of the pane as displayed, relative to the element it is mounted on.
+    ///
+    /// Positions are projected by the camera (if any), transformed by the group (if any)
+    /// and then scaled by the global zoom. The result is measured from the anchor point on the frame,
+    /// or on the parent pane for child panes, and finally shifted by the pivot.
+    pub(crate) fn local_screen_rect(&self, pane: &Pane) -> Result<Rect, DivError> {
+        let (fx, fy) = self.zoom;
+        let (mut x, mut y) = (pane.x as f32, pane.y as f32);
+        if let Some(group) = &pane.group {
+            let group = self.groups.get(group)?;
+            if let Some(camera) = &group.camera {
+                let (cx, cy) = camera.project(x, y);
+                x = cx;
+                y = cy;
+            }
+            x = group.offset.0 as f32 + group.scale * x;
+            y = group.offset.1 as f32 + group.scale * y;
+        }
+        let (sx, sy) = self.pane_scale(pane)?;
+        let (mut w, mut h) = (sx * pane.w as f32, sy * pane.h as f32);
+        if pane.auto_size.0 {
+            w = pane.node.offset_width() as f32;
+        }
+        if pane.auto_size.1 {
+            h = pane.node.offset_height() as f32;
+        }
+        let frame = self.anchor_frame(pane)?;
+        let (ax, ay) = pane.anchor.fraction();
+        let (px, py) = pane.pivot.unwrap_or((ax, ay));
+        Ok(Rect {
+            x: frame.x + (ax * frame.w as f32 + fx * x - px * w) as i32,
+            y: frame.y + (ay * frame.h as f32 + fy * y - py * h) as i32,
+            w: w as u32,
+            h: h as u32,
+        })
+    }
+    /// The rectangle the pane is anchored to on the screen, the frame or the parent pane
+    fn anchor_frame(&self, pane: &Pane) -> Result<Rect, DivError> {
+        Ok(match &pane.parent {
+            Some(parent) => {
+                let parent_rect = self.local_screen_rect(self.nodes.get(parent)?)?;
+                Rect::new(0, 0, parent_rect.w, parent_rect.h)
+            }
+            None => self.frame_rect(),
+        })
+    }
+    /// Where the position and the size of the pane are measured from, to convert them to and from
+    /// the top-left corner of the pane in unscaled units, measured from the top-left corner of the frame.
+    /// Cameras are not taken into account.
+    pub(crate) fn placement(&self, pane: &Pane) -> Result<Placement, DivError> {
+        let frame = self.anchor_frame(pane)?;
+        let (ax, ay) = pane.anchor.fraction();
+        let mut scale = self.zoom;
+        if let Some(group) = &pane.group {
+            let group = self.groups.get(group)?;
+            scale = (scale.0 * group.scale, scale.1 * group.scale);
+        }
+        Ok(Placement {
+            anchor: (ax * frame.w as f32 / scale.0, ay * frame.h as f32 / scale.1),
+            pivot: pane.pivot(),
+        })
+    }
+    /// Factors to convert sizes of the pane from frame units to pixels on the screen.
+    /// This combines the camera zoom and scale of the group with the global zoom.
+    pub(crate) fn pane_scale(&self, pane: &Pane) -> Result<(f32, f32), DivError> {
+        let mut scale = 1.0;
+        if let Some(group) = &pane.group {
+            let group = self.groups.get(group)?;
+            scale = group.scale * group.camera.map(|c| c.zoom).unwrap_or(1.0);
+        }
+        Ok((scale * self.zoom.0, scale * self.zoom.1))
+    }
+    /// The size of the pane as rendered by the browser, converted to unscaled frame units
+    pub(crate) fn measured_size(&self, p: &DivHandle) -> Result<(u32, u32), DivError> {
+        let pane = self.nodes.get(p)?;
+        let (sx, sy) = self.pane_scale(pane)?;
+        Ok((
+            (pane.node.offset_width() as f32 / sx).round() as u32,
+            (pane.node.offset_height() as f32 / sy).round() as u32,
+        ))
+    }
+    /// The area of the global frame in pixels, relative to the root element.
+    /// Without a defined size, the frame has the size of the root element.
+    pub(crate) fn frame_rect(&self) -> Rect {
+        let (w, h) = self.frame_size().unwrap_or((
+            self.root.client_width().max(0) as u32,
+            self.root.client_height().max(0) as u32,
+        ));
+        Rect::new(self.pos.0, self.pos.1, w, h)
+    }
+    /// Position and size of the pane as displayed, relative to the root element
+    pub(crate) fn screen_rect(&self, p: &DivHandle) -> Result<Rect, DivError> {
+        let pane = self.nodes.get(p)?;
+        let mut rect = self.local_screen_rect(pane)?;
+        if let Some(parent) = &pane.parent {
+            let parent_rect = self.screen_rect(parent)?;
+            rect.x += parent_rect.x;
+            rect.y += parent_rect.y;
+        }
+        Ok(rect)
+    }
 }
 
 impl Pane {
-    pub(crate) fn get_element(&self) -> Result<&HtmlElement, DivError> {
-        Ok(&self.node)
-    }
-    pub(crate) fn redraw(&self, (x, y): (i32, i32), (fx, fy): (f32, f32)) -> Result<(), DivError> {
-        let x = x + (fx * self.x as f32) as i32;
-        let y = y + (fy * self.y as f32) as i32;
-        let w = (fx * self.w as f32) as u32;
-        let h = (fy * self.h as f32) as u32;
-
+    pub(crate) fn node(&self) -> &HtmlElement {
+        &self.node
+    }
+    /// The node holding the content of the pane, which is the shadow root if there is one
+    pub(crate) fn content(&self) -> Node {
+        match &self.shadow {
+            Some(shadow) => shadow.clone().into(),
+            None => self.node.clone().into(),
+        }
+    }
+    pub(crate) fn interaction(&self) -> Option<Rc<RefCell<Interaction>>> {
+        self.interaction.clone()
+    }
+    pub(crate) fn set_interaction(&mut self, interaction: Rc<RefCell<Interaction>>) {
+        self.interaction = Some(interaction);
+    }
+    pub(crate) fn add_listener(&mut self, listener: EventListener) {
+        self.listeners.push(listener);
+    }
+    pub(crate) fn add_content_listener(&mut self, listener: EventListener) {
+        self.content_listeners.push(listener);
+    }
+    /// Drops the listeners of nodes that are no longer in the content, after replacing (parts of) it
+    pub(crate) fn drop_replaced_listeners(&mut self) {
+        let content = self.content();
+        self.content_listeners
+            .retain(|listener| content.contains(listener.target().dyn_ref::<Node>()));
+    }
+    pub(crate) fn add_signal_binding(&mut self, binding: Rc<SignalBinding>) {
+        self.signal_bindings.push(binding);
+    }
+    pub(crate) fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.w, self.h)
+    }
+    pub(crate) fn set_geometry(&mut self, Rect { x, y, w, h }: Rect) {
+        self.x = x;
+        self.y = y;
+        self.w = w;
+        self.h = h;
+    }
+    pub(crate) fn animation(&self) -> Option<&ActiveAnimation> {
+        self.animation.as_ref()
+    }
+    pub(crate) fn animation_mut(&mut self) -> Option<&mut ActiveAnimation> {
+        self.animation.as_mut()
+    }
+    pub(crate) fn set_animation(&mut self, animation: Option<ActiveAnimation>) {
+        self.animation = animation;
+    }
+    pub(crate) fn take_animation(&mut self) -> Option<ActiveAnimation> {
+        self.animation.take()
+    }
+    pub(crate) fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+    pub(crate) fn set_anchor(&mut self, anchor: Anchor) {
+        self.anchor = anchor;
+    }
+    pub(crate) fn pivot(&self) -> (f32, f32) {
+        self.pivot.unwrap_or_else(|| self.anchor.fraction())
+    }
+    pub(crate) fn set_pivot(&mut self, pivot: Option<(f32, f32)>) {
+        self.pivot = pivot;
+    }
+    pub(crate) fn parent(&self) -> Option<DivHandle> {
+        self.parent
+    }
+    /// The z-index of the pane, taking the default of 1 into account
+    pub(crate) fn z(&self) -> i32 {
+        self.z.unwrap_or(DEFAULT_Z_INDEX)
+    }
+    pub(crate) fn set_z(&mut self, z: i32) -> Result<(), DivError> {
+        self.z = Some(z);
+        self.apply_z()
+    }
+    fn apply_z(&self) -> Result<(), DivError> {
+        if let Some(z) = self.z {
+            self.node.style().set_property("z-index", &z.to_string())?;
+        }
+        Ok(())
+    }
+    pub(crate) fn group(&self) -> Option<DivGroup> {
+        self.group
+    }
+    pub(crate) fn set_group(&mut self, group: Option<DivGroup>) {
+        self.group = group;
+    }
+    /// Whether the pane is currently visible in the DOM
+    pub(crate) fn is_shown(&self) -> bool {
+        self.hidden_with.is_none()
+    }
+    pub(crate) fn set_hide_strategy(&mut self, strategy: Option<HideStrategy>) {
+        self.hide_strategy = strategy;
+    }
+    /// Shows or hides the node, where hiding uses the given strategy.
+    /// A node hidden with a different strategy is first revealed and then hidden again.
+    fn set_shown(&mut self, shown: bool, strategy: HideStrategy) -> Result<(), DivError> {
+        let target = if shown { None } else { Some(strategy) };
+        if self.hidden_with == target {
+            return Ok(());
+        }
+        match self.hidden_with {
+            Some(HideStrategy::Detach) => {
+                self.mount.append_child(&self.node)?;
+            }
+            Some(other) => self.node.class_list().remove_1(other.css_class())?,
+            None => {}
+        }
+        match target {
+            Some(HideStrategy::Detach) => self.node.remove(),
+            Some(other) => self.node.class_list().add_1(other.css_class())?,
+            None => {}
+        }
+        self.hidden_with = target;
+        Ok(())
+    }
+    pub(crate) fn set_auto_size(&mut self, auto_size: (bool, bool)) {
+        self.auto_size = auto_size;
+    }
+    pub(crate) fn set_size_limits(
+        &mut self,
+        min_size: Option<(u32, u32)>,
+        max_size: Option<(u32, u32)>,
+    ) {
+        self.min_size = min_size;
+        self.max_size = max_size;
+    }
+    /// Leaves the auto-sized dimensions to the browser, limited by the minimum and maximum size,
+    /// which are scaled with the same factors as the size.
+    pub(crate) fn apply_auto_size(&self, (sx, sy): (f32, f32)) -> Result<(), DivError> {
+        let style = self.node.style();
+        if self.auto_size.0 {
+            style.set_property("width", "auto")?;
+        }
+        if self.auto_size.1 {
+            style.set_property("height", "auto")?;
+        }
+        let limits = [
+            ("min-width", self.min_size.map(|(w, _)| sx * w as f32)),
+            ("min-height", self.min_size.map(|(_, h)| sy * h as f32)),
+            ("max-width", self.max_size.map(|(w, _)| sx * w as f32)),
+            ("max-height", self.max_size.map(|(_, h)| sy * h as f32)),
+        ];
+        for (property, limit) in limits.iter() {
+            match limit {
+                Some(px) => style.set_property(property, &format!("{}px", *px as u32))?,
+                None => {
+                    style.remove_property(property)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Writes the geometry to the node, except for auto-sized dimensions. See `apply_auto_size`.
+    pub(crate) fn redraw(&self, Rect { x, y, w, h }: Rect) -> Result<(), DivError> {
         let style = self.node.style();
         style.set_property("left", &format!("{}px", x))?;
         style.set_property("top", &format!("{}px", y))?;
-        style.set_property("width", &format!("{}px", w))?;
-        style.set_property("height", &format!("{}px", h))?;
-
+        if !self.auto_size.0 {
+            style.set_property("width", &format!("{}px", w))?;
+        }
+        if !self.auto_size.1 {
+            style.set_property("height", &format!("{}px", h))?;
+        }
         Ok(())
     }
     pub(crate) fn set_css(&self, property: &str, value: &str) -> Result<(), DivError> {
+        check_css_property(property)?;
         self.node.style().set_property(property, value)?;
         Ok(())
     }
+    /// The inline value of a CSS property, None if it is not set
+    pub(crate) fn css(&self, property: &str) -> Result<Option<String>, DivError> {
+        let value = self.node.style().get_property_value(property)?;
+        Ok(Some(value).filter(|v| !v.is_empty()))
+    }
+    pub(crate) fn remove_css(&self, property: &str) -> Result<(), DivError> {
+        check_css_property(property)?;
+        self.node.style().remove_property(property)?;
+        Ok(())
+    }
     pub(crate) fn add_class(&self, css_class: &str) -> Result<(), DivError> {
+        check_class_name(css_class)?;
         self.node.class_list().add_1(css_class)?;
         Ok(())
     }
+    pub(crate) fn has_class(&self, css_class: &str) -> bool {
+        self.node.class_list().contains(css_class)
+    }
     pub(crate) fn remove_class(&self, css_class: &str) -> Result<(), DivError> {
+        check_class_name(css_class)?;
         self.node.class_list().remove_1(css_class)?;
         Ok(())
     }
+    /// Adds the class if it is missing and removes it otherwise. Returns whether the class is present afterwards.
+    pub(crate) fn toggle_class(&self, css_class: &str) -> Result<bool, DivError> {
+        check_class_name(css_class)?;
+        Ok(self.node.class_list().toggle(css_class)?)
+    }
+    /// The classes of the node, without those managed by div internally
+    pub(crate) fn classes(&self) -> Vec<String> {
+        let list = self.node.class_list();
+        (0..list.length())
+            .filter_map(|i| list.item(i))
+            .filter(|c| !is_internal_class(c))
+            .collect()
+    }
+    /// Replaces all classes of the node, except those managed by div internally
+    pub(crate) fn replace_classes(&self, classes: &[&str]) -> Result<(), DivError> {
+        for css_class in classes {
+            check_class_name(css_class)?;
+        }
+        let list = self.node.class_list();
+        for css_class in self.classes() {
+            list.remove_1(&css_class)?;
+        }
+        for css_class in classes {
+            list.add_1(css_class)?;
+        }
+        Ok(())
+    }
+    /// Replaces the content of the pane.
+    /// The nodes of child panes, which are mounted inside of the content, must be passed as `children` to keep them.
+    pub(crate) fn set_html(
+        &mut self,
+        html: &str,
+        children: &[HtmlElement],
+    ) -> Result<(), DivError> {
+        let content = self.content();
+        // Hidden child panes may be detached, those have to stay detached
+        let attached: Vec<&HtmlElement> = children
+            .iter()
+            .filter(|child| {
+                child
+                    .parent_node()
+                    .map(|parent| parent.is_same_node(Some(&content)))
+                    .unwrap_or(false)
+            })
+            .collect();
+        match &self.shadow {
+            Some(shadow) => {
+                shadow.set_inner_html(html);
+                // The scoped style-sheet lives in the shadow root, too
+                if let Some(style) = &self.scoped_style {
+                    shadow.append_child(style)?;
+                }
+            }
+            None => self.node.set_inner_html(html),
+        }
+        for child in attached {
+            content.append_child(child)?;
+        }
+        self.drop_replaced_listeners();
+        self.collect_bindings()
+    }
+    /// All elements in the content matching the selector, looking into the shadow root if there is one
+    pub(crate) fn query_selector_all(&self, selector: &str) -> Result<NodeList, DivError> {
+        Ok(match &self.shadow {
+            Some(shadow) => shadow.query_selector_all(selector)?,
+            None => self.node.query_selector_all(selector)?,
+        })
+    }
+    pub(crate) fn query_selector(&self, selector: &str) -> Result<Option<Element>, DivError> {
+        Ok(match &self.shadow {
+            Some(shadow) => shadow.query_selector(selector)?,
+            None => self.node.query_selector(selector)?,
+        })
+    }
+    /// The nodes bound to the key with a `data-div-bind` attribute
+    pub(crate) fn bound_nodes(&self, key: &str) -> Option<&[Element]> {
+        self.bindings.get(key).map(Vec::as_slice)
+    }
+    /// Finds all nodes with a `data-div-bind` attribute in the content, replacing the previously found nodes.
+    /// Nodes in the content of child panes belong to those and are skipped.
+    pub(crate) fn collect_bindings(&mut self) -> Result<(), DivError> {
+        let list = self.query_selector_all("[data-div-bind]")?;
+        self.bindings.clear();
+        for i in 0..list.length() {
+            let element = match list.item(i).and_then(|n| n.dyn_into::<Element>().ok()) {
+                Some(element) => element,
+                None => continue,
+            };
+            if !self.owns(&element)? {
+                continue;
+            }
+            if let Some(key) = element.get_attribute("data-div-bind") {
+                self.bindings.entry(key).or_default().push(element);
+            }
+        }
+        Ok(())
+    }
+    /// Whether the element is part of the own content and not of a child pane mounted in it.
+    /// The closest pane node is searched within the shadow root, if there is one, which does not contain the own node.
+    fn owns(&self, element: &Element) -> Result<bool, DivError> {
+        Ok(match element.closest(".div-rs")? {
+            Some(pane) => pane.is_same_node(Some(&self.node)),
+            None => true,
+        })
+    }
+    pub(crate) fn set_attribute(&mut self, name: &str, value: &str) -> Result<(), DivError> {
+        check_attribute_name(name)?;
+        self.node.set_attribute(name, value)?;
+        self.attributes.insert(name.to_owned(), value.to_owned());
+        Ok(())
+    }
+    pub(crate) fn remove_attribute(&mut self, name: &str) -> Result<(), DivError> {
+        check_attribute_name(name)?;
+        self.node.remove_attribute(name)?;
+        self.attributes.remove(name);
+        Ok(())
+    }
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+    /// Describes the pane such that it can be recreated from the description.
+    /// Child panes, which are mounted inside of the content, must be passed as `children` to exclude them from the HTML.
+    pub(crate) fn snapshot(
+        &self,
+        div: DivHandle,
+        children: &[&HtmlElement],
+    ) -> Result<DivSnapshot, DivError> {
+        let style = self.node.style();
+        let mut css = vec![];
+        for property in (0..style.length()).map(|i| style.item(i)) {
+            if check_css_property(&property).is_err() {
+                continue;
+            }
+            // A running animation overwrites properties like the transition, its values are not part of the div
+            let saved = self.animation().and_then(|a| a.saved_css(&property));
+            let value = match saved {
+                Some(value) => value.to_owned(),
+                None => style.get_property_value(&property)?,
+            };
+            if !value.is_empty() {
+                css.push((property, value));
+            }
+        }
+        Ok(DivSnapshot {
+            div,
+            parent: self.parent,
+            rect: self.rect(),
+            z: self.z,
+            displayed: self.displayed,
+            classes: self.classes(),
+            css,
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            html: self.content_html(children)?,
+            scoped_css: self.scoped_css.clone(),
+            shadow: self.shadow.as_ref().map(|shadow| match shadow.mode() {
+                web_sys::ShadowRootMode::Closed => ShadowMode::Closed,
+                _ => ShadowMode::Open,
+            }),
+            hide_strategy: self.hide_strategy,
+            anchor: self.anchor,
+            pivot: self.pivot,
+            auto_size: self.auto_size,
+            min_size: self.min_size,
+            max_size: self.max_size,
+        })
+    }
+    /// The HTML of the content, without the nodes of child panes and the scoped style element
+    fn content_html(&self, children: &[&HtmlElement]) -> Result<String, DivError> {
+        let content = self.content();
+        let excluded = |node: &Node| {
+            children.iter().any(|child| node.is_same_node(Some(child)))
+                || self
+                    .scoped_style
+                    .as_ref()
+                    .map(|style| node.is_same_node(Some(style)))
+                    .unwrap_or(false)
+        };
+        let container = doc()?.create_element("div")?;
+        let nodes = content.child_nodes();
+        for node in (0..nodes.length()).filter_map(|i| nodes.item(i)) {
+            if !excluded(&node) {
+                container.append_child(&node.clone_node_with_deep(true)?)?;
+            }
+        }
+        Ok(container.inner_html())
+    }
+}
+
+/// Properties that control the position and size of the pane.
+/// div writes the physical ones whenever the pane is redrawn, see `Pane::redraw`, the others would override them.
+const GEOMETRY_PROPERTIES: [&str; 24] = [
+    "position",
+    "left",
+    "top",
+    "right",
+    "bottom",
+    "inset",
+    "inset-block",
+    "inset-block-start",
+    "inset-block-end",
+    "inset-inline",
+    "inset-inline-start",
+    "inset-inline-end",
+    "width",
+    "height",
+    "min-width",
+    "min-height",
+    "max-width",
+    "max-height",
+    "inline-size",
+    "block-size",
+    "min-inline-size",
+    "min-block-size",
+    "max-inline-size",
+    "max-block-size",
+];
+
+/// The geometry properties cannot be changed through the CSS functions, div would overwrite them on the next redraw.
+pub(crate) fn check_css_property(property: &str) -> Result<(), DivError> {
+    let property = property.trim();
+    // The z-index is kept in the pane, for hit testing and snapshots
+    if property.eq_ignore_ascii_case("z-index")
+        || GEOMETRY_PROPERTIES
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(property))
+    {
+        return Err(DivError::ReservedProperty(property.to_owned()));
+    }
+    Ok(())
+}
+
+/// Classes that div adds and removes itself, to style divs and to implement scoped CSS, hiding and resize cursors
+fn is_internal_class(css_class: &str) -> bool {
+    css_class == "div-rs"
+        || css_class.starts_with("div-rs-scope-")
+        || css_class.starts_with("div-rs-hidden-")
+        || css_class.starts_with("div-rs-resize-")
+}
+
+pub(crate) fn check_class_name(css_class: &str) -> Result<(), DivError> {
+    if is_internal_class(css_class) {
+        return Err(DivError::ReservedClass(css_class.to_owned()));
+    }
+    Ok(())
+}
+
+/// The class and style attributes are managed by div itself and cannot be set as plain attributes.
+fn check_attribute_name(name: &str) -> Result<(), DivError> {
+    if name.eq_ignore_ascii_case("class") || name.eq_ignore_ascii_case("style") {
+        return Err(DivError::ReservedAttribute(name.to_owned()));
+    }
+    Ok(())
 }
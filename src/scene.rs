@@ -0,0 +1,292 @@
+//! Declarative scene descriptions, loaded from JSON or TOML
+//!
+//! A scene is a list of named divs. Their content is either inline HTML, a template registered with the `SceneLoader`,
+//! or a JS class loaded with `div::load_js_class` and registered with the loader.
+//! The whole scene is validated before any div is created.
+//!
+//! TOML support requires the `toml` feature.
+
+use crate::pane::{check_class_name, check_css_property};
+use crate::*;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A list of divs to create, usually parsed from a file with `SceneLoader`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneDescription {
+    /// Written as `divs` in JSON or as `[[div]]` tables in TOML
+    #[serde(default, alias = "div")]
+    pub divs: Vec<DivDescription>,
+}
+
+/// A single div in a `SceneDescription`.
+///
+/// At most one of `html`, `template` and `js_class` defines the content, without any of them the div is empty.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DivDescription {
+    /// Key of the div in the map returned by the loader, unique within the scene
+    pub name: String,
+    #[serde(default)]
+    pub x: i32,
+    #[serde(default)]
+    pub y: i32,
+    #[serde(default)]
+    pub w: u32,
+    #[serde(default)]
+    pub h: u32,
+    #[serde(default)]
+    pub anchor: Anchor,
+    /// HTML id attribute
+    pub id: Option<String>,
+    #[serde(default)]
+    pub classes: Vec<String>,
+    /// Inline CSS properties by name
+    #[serde(default)]
+    pub css: BTreeMap<String, String>,
+    pub html: Option<String>,
+    /// Name of a template registered with `SceneLoader::template`
+    pub template: Option<String>,
+    /// Name of a JS class registered with `SceneLoader::js_class`
+    pub js_class: Option<String>,
+}
+
+/// Creates divs from scene descriptions.
+///
+/// Templates and JS classes referred to by the scene have to be registered before loading it.
+/// # Example
+/// ```no_run
+/// let scene = r#"{
+///     "divs": [
+///         {
+///             "name": "score",
+///             "x": -10, "y": 10, "w": 120, "h": 30,
+///             "anchor": "TopRight",
+///             "classes": ["hud"],
+///             "template": "score"
+///         },
+///         {
+///             "name": "menu",
+///             "w": 200, "h": 400,
+///             "html": "<button>Start</button>",
+///             "css": { "background-color": "black" }
+///         }
+///     ]
+/// }"#;
+/// let divs = div::SceneLoader::new()
+///     .template("score", "Score: <span data-div-bind='score'>0</span>")
+///     .load_json(scene)
+///     .unwrap();
+/// divs["score"].bind("score").set_text("10").unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SceneLoader {
+    templates: HashMap<String, String>,
+    js_classes: HashMap<String, JsClassHandle>,
+}
+
+impl SceneLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Registers HTML that divs in a scene can refer to by name
+    pub fn template(mut self, name: impl Into<String>, html: impl Into<String>) -> Self {
+        self.templates.insert(name.into(), html.into());
+        self
+    }
+    /// Registers a loaded JS class that divs in a scene can refer to by name
+    pub fn js_class(mut self, name: impl Into<String>, class: JsClassHandle) -> Self {
+        self.js_classes.insert(name.into(), class);
+        self
+    }
+    /// Parses a scene from JSON and creates its divs. See `load`.
+    pub fn load_json(&self, json: &str) -> Result<HashMap<String, DivHandle>, DivError> {
+        let scene =
+            serde_json::from_str(json).map_err(|e| DivError::DeserializeError(e.to_string()))?;
+        self.load(&scene)
+    }
+    /// Parses a scene from TOML and creates its divs. See `load`.
+    #[cfg(feature = "toml")]
+    pub fn load_toml(&self, toml: &str) -> Result<HashMap<String, DivHandle>, DivError> {
+        let scene = toml::from_str(toml).map_err(|e| DivError::DeserializeError(e.to_string()))?;
+        self.load(&scene)
+    }
+    /// Creates all divs of the scene and returns them by name.
+    ///
+    /// Nothing is created if the scene is invalid, which is reported as `DivError::InvalidScene`.
+    /// If creating a div fails anyway, the divs created before it are deleted again.
+    pub fn load(&self, scene: &SceneDescription) -> Result<HashMap<String, DivHandle>, DivError> {
+        self.validate(scene)?;
+        let mut divs = HashMap::new();
+        for description in &scene.divs {
+            match self.create(description) {
+                Ok(div) => {
+                    divs.insert(description.name.clone(), div);
+                }
+                Err(e) => {
+                    for div in divs.values_mut() {
+                        div.delete()?;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(divs)
+    }
+    fn validate(&self, scene: &SceneDescription) -> Result<(), DivError> {
+        let mut names = HashSet::new();
+        for description in &scene.divs {
+            let name = &description.name;
+            let invalid =
+                |msg: String| Err(DivError::InvalidScene(format!("div {}: {}", name, msg)));
+            if name.is_empty() {
+                return Err(DivError::InvalidScene("div without a name".to_owned()));
+            }
+            if !names.insert(name) {
+                return invalid("the name is used more than once".to_owned());
+            }
+            let sources = [
+                description.html.is_some(),
+                description.template.is_some(),
+                description.js_class.is_some(),
+            ];
+            if sources.iter().filter(|s| **s).count() > 1 {
+                return invalid("only one of html, template and js_class can be set".to_owned());
+            }
+            if let Some(id) = &description.id {
+                if id.is_empty() || id.contains(char::is_whitespace) {
+                    return invalid(format!("invalid id {:?}", id));
+                }
+            }
+            for class in &description.classes {
+                if class.is_empty() || class.contains(char::is_whitespace) {
+                    return invalid(format!("invalid class {:?}", class));
+                }
+                if let Err(e) = check_class_name(class) {
+                    return invalid(e.to_string());
+                }
+            }
+            for property in description.css.keys() {
+                if let Err(e) = check_css_property(property) {
+                    return invalid(e.to_string());
+                }
+            }
+            if let Some(template) = &description.template {
+                if !self.templates.contains_key(template) {
+                    return invalid(format!("unknown template {}", template));
+                }
+            }
+            if let Some(class) = &description.js_class {
+                if !self.js_classes.contains_key(class) {
+                    return invalid(format!("unknown JS class {}", class));
+                }
+            }
+        }
+        Ok(())
+    }
+    fn create(&self, description: &DivDescription) -> Result<DivHandle, DivError> {
+        let DivDescription { x, y, w, h, .. } = *description;
+        let css: Vec<(&str, &str)> = description
+            .css
+            .iter()
+            .map(|(property, value)| (property.as_str(), value.as_str()))
+            .collect();
+        let div = match &description.js_class {
+            Some(class) => {
+                let div = from_js_class(x, y, w, h, self.js_classes[class])?;
+                for class in &description.classes {
+                    div.add_class(class)?;
+                }
+                for (property, value) in &css {
+                    div.set_css(property, value)?;
+                }
+                div
+            }
+            None => {
+                let html = match &description.template {
+                    Some(template) => &self.templates[template],
+                    None => description.html.as_deref().unwrap_or_default(),
+                };
+                new_styled(x, y, w, h, html, &description.classes, &css)?
+            }
+        };
+        if description.anchor != Anchor::default() {
+            div.set_anchor(description.anchor)?;
+        }
+        if let Some(id) = &description.id {
+            div.set_id(id)?;
+        }
+        Ok(div)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(description: DivDescription) -> SceneDescription {
+        SceneDescription {
+            divs: vec![DivDescription {
+                name: "a".to_owned(),
+                ..description
+            }],
+        }
+    }
+
+    #[test]
+    fn validates_classes_and_ids() {
+        let loader = SceneLoader::new();
+        let valid = DivDescription {
+            id: Some("main".to_owned()),
+            classes: vec!["hud".to_owned(), "top-bar".to_owned()],
+            ..Default::default()
+        };
+        assert!(loader.validate(&scene(valid)).is_ok());
+        for class in ["", "two classes", "div-rs-hidden-display", "div-rs"] {
+            let description = DivDescription {
+                classes: vec![class.to_owned()],
+                ..Default::default()
+            };
+            assert!(matches!(
+                loader.validate(&scene(description)),
+                Err(DivError::InvalidScene(_))
+            ));
+        }
+        for id in ["", "main menu"] {
+            let description = DivDescription {
+                id: Some(id.to_owned()),
+                ..Default::default()
+            };
+            assert!(matches!(
+                loader.validate(&scene(description)),
+                Err(DivError::InvalidScene(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn validates_names_and_sources() {
+        let loader = SceneLoader::new().template("t", "<p></p>");
+        let twice = SceneDescription {
+            divs: vec![scene(Default::default()).divs[0].clone(); 2],
+        };
+        assert!(loader.validate(&twice).is_err());
+        let both = DivDescription {
+            html: Some("<p></p>".to_owned()),
+            template: Some("t".to_owned()),
+            ..Default::default()
+        };
+        assert!(loader.validate(&scene(both)).is_err());
+        let unknown = DivDescription {
+            template: Some("u".to_owned()),
+            ..Default::default()
+        };
+        assert!(loader.validate(&scene(unknown)).is_err());
+        let reserved = DivDescription {
+            css: std::iter::once(("width".to_owned(), "10px".to_owned())).collect(),
+            ..Default::default()
+        };
+        assert!(loader.validate(&scene(reserved)).is_err());
+    }
+}
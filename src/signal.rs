@@ -0,0 +1,412 @@
+//! Reactive values that drive the content, style, visibility and geometry of divs
+//!
+//! Binding a div property to a signal registers a `SignalBinding` on the pane.
+//! Setting a signal marks the bindings depending on it as dirty, which are then applied together in one flush,
+//! scheduled with `requestAnimationFrame`. Bindings of signals that have not changed are not touched.
+//! Derived signals created with `Signal::map` are updated immediately when their source changes.
+//!
+//! No borrow of a value is held while user code runs, so signals can be set from within bindings and `Signal::with`.
+
+use crate::state;
+use crate::*;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::{Rc, Weak};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// A value that divs can be bound to, updating the divs whenever it changes.
+///
+/// Signals are cheap to clone, all clones share the same value.
+/// A div keeps the signals it is bound to alive, including derived signals created with `map` just for the binding.
+/// It stops following them when it is deleted.
+/// # Example
+/// ```no_run
+/// use div::Signal;
+/// let score = Signal::new(0);
+/// let div = div::new(0, 0, 200, 50, "").unwrap();
+/// div.bind_text(&score.map(|s| format!("Score: {}", s))).unwrap();
+/// div.bind_class("highscore", &score.map(|s| *s > 100)).unwrap();
+/// // Updates the DOM on the next animation frame
+/// score.set(150);
+/// ```
+pub struct Signal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+struct SignalInner<T> {
+    /// Shared with running bindings and `with` closures, such that the value can be replaced meanwhile
+    value: RefCell<Rc<T>>,
+    subscribers: RefCell<Vec<Weak<dyn Subscriber>>>,
+    /// Subscribers computing this signal from other signals
+    sources: RefCell<Vec<Rc<dyn Subscriber>>>,
+}
+
+trait Subscriber {
+    fn notify(self: Rc<Self>);
+}
+
+/// Applies the value of a signal to a pane. Owned by the pane, such that it is dropped with it.
+///
+/// Not to be confused with the public `Binding` of nodes marked with `data-div-bind`.
+pub(crate) struct SignalBinding {
+    dirty: Cell<bool>,
+    apply: Box<dyn Fn() -> Result<(), DivError>>,
+}
+
+struct Map<T, U, F> {
+    source: Weak<SignalInner<T>>,
+    target: Weak<SignalInner<U>>,
+    f: F,
+}
+
+#[derive(Default)]
+struct FlushQueue {
+    bindings: Vec<Rc<SignalBinding>>,
+    scheduled: bool,
+}
+
+thread_local! {
+    static QUEUE: RefCell<FlushQueue> = RefCell::new(FlushQueue::default());
+}
+
+impl<T: 'static> Signal<T> {
+    pub fn new(value: T) -> Self {
+        Signal {
+            inner: Rc::new(SignalInner {
+                value: RefCell::new(Rc::new(value)),
+                subscribers: RefCell::new(vec![]),
+                sources: RefCell::new(vec![]),
+            }),
+        }
+    }
+    /// A copy of the current value
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        T::clone(&self.inner.value.borrow())
+    }
+    /// Calls the closure with a reference to the current value.
+    /// Setting the signal inside of the closure does not change the value it has been called with.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.current())
+    }
+    /// Replaces the value and updates everything bound to the signal
+    pub fn set(&self, value: T) {
+        *self.inner.value.borrow_mut() = Rc::new(value);
+        self.inner.notify_all();
+    }
+    /// Modifies the value in place and updates everything bound to the signal.
+    /// The value is copied first if it is still in use by `with` or by a binding that is being applied.
+    /// # Panics
+    /// If the closure accesses the same signal.
+    pub fn update(&self, f: impl FnOnce(&mut T))
+    where
+        T: Clone,
+    {
+        f(Rc::make_mut(&mut self.inner.value.borrow_mut()));
+        self.inner.notify_all();
+    }
+    /// Creates a signal whose value is computed from this signal, and recomputed whenever it changes.
+    /// The derived signal keeps following this signal as long as it is alive.
+    pub fn map<U: 'static, F>(&self, f: F) -> Signal<U>
+    where
+        F: Fn(&T) -> U + 'static,
+    {
+        let target = Signal::new(f(&self.inner.current()));
+        let map = Rc::new(Map {
+            source: Rc::downgrade(&self.inner),
+            target: Rc::downgrade(&target.inner),
+            f,
+        });
+        self.subscribe(map.clone());
+        target.inner.sources.borrow_mut().push(map);
+        target
+    }
+    fn subscribe(&self, subscriber: Rc<dyn Subscriber>) {
+        self.inner
+            .subscribers
+            .borrow_mut()
+            .push(Rc::downgrade(&subscriber));
+    }
+}
+
+impl<T> SignalInner<T> {
+    /// The current value, without keeping it borrowed
+    fn current(&self) -> Rc<T> {
+        self.value.borrow().clone()
+    }
+    fn notify_all(&self) {
+        let subscribers: Vec<_> = {
+            let mut subscribers = self.subscribers.borrow_mut();
+            subscribers.retain(|s| s.strong_count() > 0);
+            subscribers.iter().filter_map(Weak::upgrade).collect()
+        };
+        for subscriber in subscribers {
+            subscriber.notify();
+        }
+    }
+}
+
+impl<T: 'static, U: 'static, F: Fn(&T) -> U> Subscriber for Map<T, U, F> {
+    fn notify(self: Rc<Self>) {
+        if let (Some(source), Some(target)) = (self.source.upgrade(), self.target.upgrade()) {
+            let value = (self.f)(&source.current());
+            *target.value.borrow_mut() = Rc::new(value);
+            target.notify_all();
+        }
+    }
+}
+
+impl SignalBinding {
+    /// Creates a binding that applies the current value of the signal, without subscribing it yet
+    fn new<T: 'static, F>(signal: &Signal<T>, apply: F) -> Rc<Self>
+    where
+        F: Fn(&T) -> Result<(), DivError> + 'static,
+    {
+        // Keeps the signal alive, which would otherwise be dropped right away for a temporary derived signal
+        let source = signal.inner.clone();
+        Rc::new(SignalBinding {
+            dirty: Cell::new(false),
+            apply: Box::new(move || apply(&source.current())),
+        })
+    }
+}
+
+impl Subscriber for SignalBinding {
+    fn notify(self: Rc<Self>) {
+        if self.dirty.replace(true) {
+            return;
+        }
+        let schedule = QUEUE.with(|queue| {
+            let mut queue = queue.borrow_mut();
+            queue.bindings.push(self);
+            !std::mem::replace(&mut queue.scheduled, true)
+        });
+        if schedule {
+            request_flush();
+        }
+    }
+}
+
+/// Schedules a flush on the next animation frame.
+/// Outside of the browser there are no animation frames, the tests call `flush` directly.
+fn request_flush() {
+    if !cfg!(target_arch = "wasm32") {
+        return;
+    }
+    if let Some(window) = web_sys::window() {
+        let callback = Closure::once_into_js(|_: JsValue| flush());
+        let _ = window.request_animation_frame(callback.unchecked_ref());
+    }
+}
+
+/// Applies all dirty bindings
+fn flush() {
+    let bindings = QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        queue.scheduled = false;
+        std::mem::take(&mut queue.bindings)
+    });
+    for binding in bindings {
+        binding.dirty.set(false);
+        // Fails only if the div has been deleted in the meantime, in which case there is nothing to update
+        let _ = (binding.apply)();
+    }
+}
+
+impl DivHandle {
+    /// Displays the value of the signal as plain text content of the div
+    pub fn bind_text<T: fmt::Display + 'static>(&self, signal: &Signal<T>) -> Result<(), DivError> {
+        self.bind_signal(signal, |div, value| {
+            div.set_html(&Html::text(&value.to_string()))
+        })
+    }
+    /// Adds the CSS class to the div while the signal is true and removes it otherwise
+    pub fn bind_class(&self, css_class: &str, signal: &Signal<bool>) -> Result<(), DivError> {
+        let css_class = css_class.to_owned();
+        self.bind_signal(signal, move |div, add| {
+            if *add {
+                div.add_class(&css_class)
+            } else {
+                div.remove_class(&css_class)
+            }
+        })
+    }
+    /// Sets the CSS property to the value of the signal. The same properties as in `set_css` are protected.
+    pub fn bind_css<T: fmt::Display + 'static>(
+        &self,
+        property: &str,
+        signal: &Signal<T>,
+    ) -> Result<(), DivError> {
+        let property = property.to_owned();
+        self.bind_signal(signal, move |div, value| {
+            div.set_css(&property, &value.to_string())
+        })
+    }
+    /// Shows the div while the signal is true and hides it otherwise
+    pub fn bind_visible(&self, signal: &Signal<bool>) -> Result<(), DivError> {
+        self.bind_signal(
+            signal,
+            |div, visible| {
+                if *visible {
+                    div.show()
+                } else {
+                    div.hide()
+                }
+            },
+        )
+    }
+    /// Moves the div to the position of the signal, see `reposition`
+    pub fn bind_position(&self, signal: &Signal<(i32, i32)>) -> Result<(), DivError> {
+        self.bind_signal(signal, |div, (x, y)| div.reposition(*x, *y))
+    }
+    /// Resizes the div to the size of the signal, see `resize`
+    pub fn bind_size(&self, signal: &Signal<(u32, u32)>) -> Result<(), DivError> {
+        self.bind_signal(signal, |div, (w, h)| div.resize(*w, *h))
+    }
+    /// Moves and resizes the div to the rectangle of the signal
+    pub fn bind_rect(&self, signal: &Signal<Rect>) -> Result<(), DivError> {
+        self.bind_signal(signal, |div, rect| {
+            div.reposition_and_resize(rect.x, rect.y, rect.w, rect.h)
+        })
+    }
+    /// Applies the current value right away and registers a binding for future changes
+    fn bind_signal<T: 'static, F>(&self, signal: &Signal<T>, apply: F) -> Result<(), DivError>
+    where
+        F: Fn(DivHandle, &T) -> Result<(), DivError> + 'static,
+    {
+        let div = *self;
+        let binding = SignalBinding::new(signal, move |value| apply(div, value));
+        (binding.apply)()?;
+        state::exec_mut(|state| {
+            state
+                .nodes
+                .get_mut(self)?
+                .add_signal_binding(binding.clone());
+            Ok(())
+        })?;
+        signal.subscribe(binding);
+        Ok(())
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Signal {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Signal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Signal")
+            .field(&self.inner.value.borrow())
+            .finish()
+    }
+}
+
+impl fmt::Debug for SignalBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SignalBinding")
+            .field("dirty", &self.dirty.get())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A binding that records every value it is applied with
+    fn recorder<T: Clone + 'static>(
+        signal: &Signal<T>,
+    ) -> (Rc<SignalBinding>, Rc<RefCell<Vec<T>>>) {
+        let applied = Rc::new(RefCell::new(vec![]));
+        let log = applied.clone();
+        let binding = SignalBinding::new(signal, move |value: &T| {
+            log.borrow_mut().push(value.clone());
+            Ok(())
+        });
+        signal.subscribe(binding.clone());
+        (binding, applied)
+    }
+
+    fn queued() -> usize {
+        QUEUE.with(|queue| queue.borrow().bindings.len())
+    }
+
+    #[test]
+    fn applies_dirty_bindings_once() {
+        let signal = Signal::new(1);
+        let (_binding, applied) = recorder(&signal);
+        signal.set(2);
+        signal.set(3);
+        signal.update(|v| *v += 1);
+        assert_eq!(queued(), 1);
+        flush();
+        assert_eq!(*applied.borrow(), vec![4]);
+        assert_eq!(queued(), 0);
+        flush();
+        assert_eq!(*applied.borrow(), vec![4]);
+        signal.set(5);
+        flush();
+        assert_eq!(*applied.borrow(), vec![4, 5]);
+    }
+
+    #[test]
+    fn propagates_through_maps() {
+        let signal = Signal::new(2);
+        let doubled = signal.map(|v| v * 2);
+        let text = doubled.map(|v| format!("{}!", v));
+        assert_eq!(text.get(), "4!");
+        signal.set(5);
+        assert_eq!(doubled.get(), 10);
+        assert_eq!(text.get(), "10!");
+        let (_binding, applied) = recorder(&text);
+        signal.update(|v| *v += 1);
+        flush();
+        assert_eq!(*applied.borrow(), vec!["12!".to_owned()]);
+    }
+
+    #[test]
+    fn follows_temporary_maps() {
+        let signal = Signal::new(1);
+        let (binding, applied) = recorder(&signal.map(|v| v + 1));
+        signal.set(2);
+        flush();
+        assert_eq!(*applied.borrow(), vec![3]);
+        drop(binding);
+        signal.set(3);
+        assert_eq!(queued(), 0);
+    }
+
+    #[test]
+    fn sets_signals_while_in_use() {
+        let signal = Signal::new(1);
+        let copy = signal.clone();
+        let binding = SignalBinding::new(&signal, move |value: &i32| {
+            if *value < 3 {
+                copy.set(value + 1);
+            }
+            Ok(())
+        });
+        signal.subscribe(binding.clone());
+        signal.set(2);
+        flush();
+        assert_eq!(signal.get(), 3);
+        assert_eq!(queued(), 1);
+        flush();
+        assert_eq!(queued(), 0);
+
+        let seen = signal.with(|value| {
+            signal.set(10);
+            signal.update(|v| *v += 1);
+            *value
+        });
+        assert_eq!((seen, signal.get()), (3, 11));
+        flush();
+    }
+}
@@ -0,0 +1,174 @@
+//! Snapshots of all divs and the global frame, to save a scene and rebuild it later
+//!
+//! With the `serde` feature, snapshots can be serialized with any serde format.
+//!
+//! Not included in snapshots are groups, event handlers, drag and resize settings, signal bindings and running animations.
+//! The HTML content is captured as it is currently in the DOM, including changes made by the user or by scripts.
+
+use crate::state;
+use crate::storage::PaneStorage;
+use crate::*;
+use std::collections::HashMap;
+
+/// Description of the global frame and all divs in it, created with `div::snapshot()`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// The global origin
+    pub origin: (i32, i32),
+    /// The size of the frame when initializing, None if it has not been defined
+    pub size: Option<(u32, u32)>,
+    /// The factors from the original size to the current size of the frame
+    pub zoom: (f32, f32),
+    pub hide_strategy: HideStrategy,
+    /// All divs, parents always before their children
+    pub divs: Vec<DivSnapshot>,
+}
+
+/// Description of a single div, part of a `Snapshot`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DivSnapshot {
+    /// The handle of the div when the snapshot was taken
+    pub div: DivHandle,
+    pub parent: Option<DivHandle>,
+    /// Position and size, in the original scale when initializing
+    pub rect: Rect,
+    pub z: Option<i32>,
+    /// False if the div has been hidden with `hide`
+    pub displayed: bool,
+    /// CSS classes, without those used by div internally
+    pub classes: Vec<String>,
+    /// Inline CSS properties, without those controlled by div. Properties of a running animation have their value from before it.
+    pub css: Vec<(String, String)>,
+    /// HTML attributes set through div, including the id
+    pub attributes: Vec<(String, String)>,
+    /// The HTML content, without child divs
+    pub html: String,
+    /// The scoped style-sheets, as they have been added
+    pub scoped_css: Vec<String>,
+    pub shadow: Option<ShadowMode>,
+    pub hide_strategy: Option<HideStrategy>,
+    pub anchor: Anchor,
+    pub pivot: Option<(f32, f32)>,
+    pub auto_size: (bool, bool),
+    pub min_size: Option<(u32, u32)>,
+    pub max_size: Option<(u32, u32)>,
+}
+
+/// Describes all divs and the global frame, such that they can be rebuilt with `div::restore`.
+/// # Example
+/// ```no_run
+/// let snapshot = div::snapshot().unwrap();
+/// div::delete_all().unwrap();
+/// let handles = div::restore(&snapshot).unwrap();
+/// ```
+pub fn snapshot() -> Result<Snapshot, DivError> {
+    state::exec(|state| {
+        let mut divs = vec![];
+        for handle in state.nodes.handles() {
+            let children = state
+                .child_panes(&handle)
+                .iter()
+                .map(|child| Ok(state.nodes.get(child)?.node()))
+                .collect::<Result<Vec<_>, DivError>>()?;
+            divs.push(state.nodes.get(&handle)?.snapshot(handle, &children)?);
+        }
+        Ok(Snapshot {
+            origin: state.pos,
+            size: state.size,
+            zoom: state.zoom,
+            hide_strategy: state.hide_strategy,
+            divs,
+        })
+    })
+}
+
+/// Creates all divs of a snapshot and applies its global frame settings.
+///
+/// Existing divs are not deleted, but they are affected by the restored frame settings.
+/// Returns the new handle of each div, by the handle it had when the snapshot was taken.
+///
+/// If any div cannot be created, the divs created so far are deleted and the frame settings are left unchanged.
+/// A snapshot that lists a child before its parent fails with `DivError::InvalidSnapshot` before anything is changed.
+pub fn restore(snapshot: &Snapshot) -> Result<HashMap<DivHandle, DivHandle>, DivError> {
+    // Parents have to come before their children, which is checked before changing anything
+    for (i, div) in snapshot.divs.iter().enumerate() {
+        if let Some(parent) = div.parent {
+            if !snapshot.divs[..i].iter().any(|other| other.div == parent) {
+                return Err(parent_missing(div));
+            }
+        }
+    }
+    state::exec_mut(|state| {
+        let previous = (state.pos, state.size, state.zoom, state.hide_strategy);
+        state.pos = snapshot.origin;
+        state.size = snapshot.size;
+        state.zoom = snapshot.zoom;
+        state.hide_strategy = snapshot.hide_strategy;
+        let mut handles = HashMap::new();
+        if let Err(e) = state.restore_divs(snapshot, &mut handles) {
+            for handle in handles.values() {
+                // Child divs are deleted together with their parent
+                if state.nodes.get(handle).is_ok() {
+                    state.delete_pane(handle)?;
+                }
+            }
+            let (pos, size, zoom, hide_strategy) = previous;
+            state.pos = pos;
+            state.size = size;
+            state.zoom = zoom;
+            state.hide_strategy = hide_strategy;
+            return Err(e);
+        }
+        state.redraw_all()?;
+        Ok(handles)
+    })
+}
+
+impl<PS: PaneStorage, CS: ClassStorage> GlobalState<PS, CS> {
+    /// Creates the divs of the snapshot, collecting the new handles as they are created
+    fn restore_divs(
+        &mut self,
+        snapshot: &Snapshot,
+        handles: &mut HashMap<DivHandle, DivHandle>,
+    ) -> Result<(), DivError> {
+        for div in &snapshot.divs {
+            let parent = match div.parent {
+                Some(parent) => Some(*handles.get(&parent).ok_or_else(|| parent_missing(div))?),
+                None => None,
+            };
+            let builder = DivBuilder {
+                x: div.rect.x,
+                y: div.rect.y,
+                w: div.rect.w,
+                h: div.rect.h,
+                html: div.html.clone(),
+                classes: div.classes.clone(),
+                css: div.css.clone(),
+                attributes: div.attributes.clone(),
+                z: div.z,
+                hidden: !div.displayed,
+                parent,
+                auto_size: div.auto_size,
+                min_size: div.min_size,
+                max_size: div.max_size,
+                scoped_css: div.scoped_css.clone(),
+                hide_strategy: div.hide_strategy,
+                anchor: div.anchor,
+                pivot: div.pivot,
+                shadow: div.shadow,
+                ..DivBuilder::default()
+            };
+            handles.insert(div.div, self.new_pane(&builder)?);
+        }
+        Ok(())
+    }
+}
+
+fn parent_missing(div: &DivSnapshot) -> DivError {
+    DivError::InvalidSnapshot(format!(
+        "the parent of div {:?} does not come before it",
+        div.div
+    ))
+}
@@ -8,7 +8,9 @@ where
     pub(crate) pos: (i32, i32),
     pub(crate) size: Option<(u32, u32)>,
     pub(crate) zoom: (f32, f32),
+    pub(crate) hide_strategy: HideStrategy,
     pub(crate) nodes: PS,
+    pub(crate) groups: GroupStorage,
     pub(crate) classes: CS,
 }
 use std::thread_local;
@@ -8,9 +8,8 @@ pub(crate) trait PaneStorage {
     fn remove(&mut self, p: &DivHandle) -> Result<Pane, DivError>;
     fn get(&self, p: &DivHandle) -> Result<&Pane, DivError>;
     fn get_mut(&mut self, p: &DivHandle) -> Result<&mut Pane, DivError>;
-    fn for_each<F>(&mut self, f: &F) -> Result<(), DivError>
-    where
-        F: Fn(&mut Pane) -> Result<(), DivError>;
+    /// All handles currently in use, in the order the panes have been created
+    fn handles(&self) -> Vec<DivHandle>;
 }
 /// A trait for data structures which store information about JS classes loaded in
 pub(crate) trait ClassStorage {
@@ -20,51 +19,75 @@ pub(crate) trait ClassStorage {
 /// At the moment, uses a std HashMap internally.
 #[derive(Debug, Default)]
 pub(crate) struct PaneHashMap {
-    data: HashMap<usize, Pane>,
-    next_idx: usize,
+    data: IndexedMap<Pane>,
 }
 
 impl PaneStorage for PaneHashMap {
     fn insert(&mut self, p: Pane) -> DivHandle {
-        let i = self.next_id();
-        self.data.insert(i, p);
-        DivHandle(i)
+        DivHandle(self.data.insert(p))
     }
     fn remove(&mut self, p: &DivHandle) -> Result<Pane, DivError> {
-        self.data
-            .remove(&p.0)
-            .ok_or_else(|| index_error(&p, self.next_idx))
+        self.data.remove(p.0)
     }
     fn get(&self, p: &DivHandle) -> Result<&Pane, DivError> {
-        self.data
-            .get(&p.0)
-            .ok_or_else(|| index_error(p, self.next_idx))
+        self.data.get(p.0)
     }
     fn get_mut(&mut self, p: &DivHandle) -> Result<&mut Pane, DivError> {
-        let idx = self.next_idx;
-        self.data.get_mut(&p.0).ok_or_else(|| index_error(p, idx))
+        self.data.get_mut(p.0)
+    }
+    fn handles(&self) -> Vec<DivHandle> {
+        self.data.indices().into_iter().map(DivHandle).collect()
     }
-    fn for_each<F>(&mut self, f: &F) -> Result<(), DivError>
-    where
-        F: Fn(&mut Pane) -> Result<(), DivError>,
-    {
-        for pane in self.data.values_mut() {
-            f(pane)?;
+}
+
+/// Values stored by an index that is unique for the lifetime of the map, indices of removed values are never reused.
+#[derive(Debug)]
+pub(crate) struct IndexedMap<T> {
+    data: HashMap<usize, T>,
+    next_idx: usize,
+}
+
+impl<T> Default for IndexedMap<T> {
+    fn default() -> Self {
+        IndexedMap {
+            data: HashMap::new(),
+            next_idx: 0,
         }
-        Ok(())
     }
 }
 
-impl PaneHashMap {
-    fn next_id(&mut self) -> usize {
+impl<T> IndexedMap<T> {
+    pub(crate) fn insert(&mut self, value: T) -> usize {
         let i = self.next_idx;
         self.next_idx = i + 1;
+        self.data.insert(i, value);
         i
     }
+    pub(crate) fn remove(&mut self, i: usize) -> Result<T, DivError> {
+        let next_idx = self.next_idx;
+        self.data.remove(&i).ok_or_else(|| index_error(i, next_idx))
+    }
+    pub(crate) fn get(&self, i: usize) -> Result<&T, DivError> {
+        self.data
+            .get(&i)
+            .ok_or_else(|| index_error(i, self.next_idx))
+    }
+    pub(crate) fn get_mut(&mut self, i: usize) -> Result<&mut T, DivError> {
+        let next_idx = self.next_idx;
+        self.data
+            .get_mut(&i)
+            .ok_or_else(|| index_error(i, next_idx))
+    }
+    /// All indices currently in use, in the order the values have been inserted
+    pub(crate) fn indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.data.keys().copied().collect();
+        indices.sort_unstable();
+        indices
+    }
 }
 
-fn index_error(p: &DivHandle, max_idx: usize) -> DivError {
-    if max_idx > p.0 {
+fn index_error(i: usize, max_idx: usize) -> DivError {
+    if max_idx > i {
         DivError::UseAfterDelete
     } else {
         DivError::NotAllocated
@@ -1,19 +1,396 @@
 use crate::{error::*, utils::doc};
+use std::cell::RefCell;
+use web_sys::{CssStyleSheet, ShadowRoot};
 
-pub(crate) fn add_div_styles_to_document() -> Result<(), DivError> {
-    let css = r#"
-    .div-rs {
+/// The z-index set on all divs by the div-rs stylesheet
+pub(crate) const DEFAULT_Z_INDEX: i32 = 1;
+
+/// How divs are hidden when calling `hide`, or when their group is hidden.
+///
+/// Detaching a div from the DOM is the most thorough way to hide it. But re-attaching it later resets the
+/// scroll position, restarts CSS animations, reloads embedded media and changes the stacking order among divs with the same z-index.
+/// The CSS based strategies avoid these effects, at the cost of keeping the hidden nodes in the DOM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HideStrategy {
+    /// Remove the node from the DOM
+    #[default]
+    Detach,
+    /// Keep the node in the DOM with `display: none`
+    DisplayNone,
+    /// Keep the node in the DOM with `visibility: hidden`, it still takes up space
+    VisibilityHidden,
+}
+
+impl HideStrategy {
+    /// Class used by the CSS based strategies, defined in the div-rs stylesheet
+    pub(crate) fn css_class(&self) -> &'static str {
+        match self {
+            HideStrategy::Detach => "",
+            HideStrategy::DisplayNone => "div-rs-hidden-display",
+            HideStrategy::VisibilityHidden => "div-rs-hidden-visibility",
+        }
+    }
+}
+
+/// Mode of the shadow root attached to a div, see `DivBuilder::shadow`.
+///
+/// Either way, div keeps a reference to the shadow root and renders into it.
+/// The mode only decides whether other scripts on the page can reach it through `Element.shadowRoot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShadowMode {
+    Open,
+    Closed,
+}
+
+impl ShadowMode {
+    pub(crate) fn to_web_sys(self) -> web_sys::ShadowRootMode {
+        match self {
+            ShadowMode::Open => web_sys::ShadowRootMode::Open,
+            ShadowMode::Closed => web_sys::ShadowRootMode::Closed,
+        }
+    }
+}
+
+thread_local! {
+    /// The div-rs stylesheet shared by all shadow roots, created with the first one
+    static SHADOW_STYLES: RefCell<Option<CssStyleSheet>> = const { RefCell::new(None) };
+}
+
+fn div_styles() -> String {
+    format!(
+        r#"
+    .div-rs {{
         position: absolute;
         display: unset;
-        z-index: 1;
+        z-index: {};
         overflow: hidden;
-    }
-    "#;
+    }}
+    .div-rs-hidden-display {{
+        display: none !important;
+    }}
+    .div-rs-hidden-visibility {{
+        visibility: hidden !important;
+    }}
+    .div-rs-divider {{
+        background-color: rgba(128, 128, 128, 0.5);
+    }}
+    .div-rs-resize-ns {{
+        cursor: ns-resize !important;
+    }}
+    .div-rs-resize-ew {{
+        cursor: ew-resize !important;
+    }}
+    .div-rs-resize-nwse {{
+        cursor: nwse-resize !important;
+    }}
+    .div-rs-resize-nesw {{
+        cursor: nesw-resize !important;
+    }}
+    "#,
+        DEFAULT_Z_INDEX
+    )
+}
 
+pub(crate) fn add_div_styles_to_document() -> Result<(), DivError> {
+    let css = div_styles();
     let head = doc()?.head().ok_or(DivError::MissingHead)?;
     let style = doc()?.create_element("style")?;
     style.set_attribute("type", "text/css")?;
-    style.set_text_content(Some(css));
+    style.set_text_content(Some(&css));
     head.append_child(&style)?;
     Ok(())
 }
+
+/// Adds the div-rs stylesheet to a shadow root, which is not reached by the stylesheet in the document head.
+/// Child divs mounted inside of the shadow root need it for their positioning and hiding.
+pub(crate) fn add_div_styles_to_shadow(shadow: &ShadowRoot) -> Result<(), DivError> {
+    let sheet = SHADOW_STYLES.with(|cached| -> Result<CssStyleSheet, DivError> {
+        let mut cached = cached.borrow_mut();
+        if let Some(sheet) = cached.as_ref() {
+            return Ok(sheet.clone());
+        }
+        let sheet = CssStyleSheet::new()?;
+        sheet.replace_sync(&div_styles())?;
+        *cached = Some(sheet.clone());
+        Ok(sheet)
+    })?;
+    // Adopted style-sheets are not part of the content, replacing the content keeps them
+    let sheets = shadow.adopted_style_sheets();
+    sheets.push(&sheet);
+    shadow.set_adopted_style_sheets(&sheets);
+    Ok(())
+}
+
+/// Creates an empty style element for the scoped CSS of a single div.
+/// It is appended to the given parent, or to the document head if there is none.
+pub(crate) fn add_scoped_style_element(
+    parent: Option<&web_sys::Node>,
+) -> Result<web_sys::Element, DivError> {
+    let style = doc()?.create_element("style")?;
+    style.set_attribute("type", "text/css")?;
+    match parent {
+        Some(parent) => parent.append_child(&style)?,
+        None => doc()?
+            .head()
+            .ok_or(DivError::MissingHead)?
+            .append_child(&style)?,
+    };
+    Ok(style)
+}
+
+/// Rewrites a style-sheet such that all selectors only match within the element with the given scope selector.
+///
+/// Selectors are prefixed with the scope, `:scope` and a leading `&` refer to the scope element itself.
+/// Rules inside `@media`, `@supports`, `@container` and `@layer` blocks are scoped as well,
+/// other at-rules, like `@keyframes` and `@font-face`, are kept as they are.
+pub(crate) fn scope_css(css: &str, scope: &str) -> String {
+    rewrite_selectors(css, &|selectors| scope_selectors(selectors, scope))
+}
+
+/// Rewrites a style-sheet for use inside the shadow root of a div.
+///
+/// The shadow root already encapsulates the style-sheet, only `:scope` and a leading `&` are replaced to select the host.
+pub(crate) fn host_css(css: &str) -> String {
+    rewrite_selectors(css, &host_selectors)
+}
+
+/// Applies `rewrite` to the selector list of every style rule, recursing into grouping at-rules
+fn rewrite_selectors(css: &str, rewrite: &dyn Fn(&str) -> String) -> String {
+    let css = strip_comments(css);
+    let mut out = String::new();
+    let mut rest = css.as_str();
+    while let Some(open) = find_top_level(rest, '{') {
+        let mut prelude = &rest[..open];
+        // Statements like @import end with a semicolon and have no block
+        if let Some(semicolon) = top_level_chars(prelude)
+            .filter(|(_, c)| *c == ';')
+            .last()
+            .map(|(i, _)| i)
+        {
+            out += prelude[..=semicolon].trim();
+            out += "\n";
+            prelude = &prelude[semicolon + 1..];
+        }
+        let prelude = prelude.trim();
+        let close = matching_brace(rest, open);
+        let body = &rest[open + 1..close];
+        if ["@media", "@supports", "@container", "@layer"]
+            .iter()
+            .any(|at| prelude.starts_with(at))
+        {
+            out += &format!("{} {{\n{}}}\n", prelude, rewrite_selectors(body, rewrite));
+        } else if prelude.starts_with('@') {
+            out += &format!("{} {{{}}}\n", prelude, body);
+        } else {
+            out += &format!("{} {{{}}}\n", rewrite(prelude), body);
+        }
+        rest = &rest[(close + 1).min(rest.len())..];
+    }
+    out += rest.trim();
+    out
+}
+
+fn scope_selectors(selectors: &str, scope: &str) -> String {
+    split_top_level(selectors, ',')
+        .iter()
+        .map(|selector| {
+            let selector = selector.trim();
+            if selector.contains(":scope") {
+                selector.replace(":scope", scope)
+            } else if let Some(rest) = selector.strip_prefix('&') {
+                format!("{}{}", scope, rest)
+            } else {
+                format!("{} {}", scope, selector)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn host_selectors(selectors: &str) -> String {
+    split_top_level(selectors, ',')
+        .iter()
+        .map(|selector| {
+            let selector = selector.trim();
+            if let Some(rest) = selector.strip_prefix('&') {
+                // The host can only be matched with a compound selector inside the :host() function
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || ">+~".contains(c))
+                    .unwrap_or(rest.len());
+                match &rest[..end] {
+                    "" => format!(":host{}", rest),
+                    compound => format!(":host({}){}", compound, &rest[end..]),
+                }
+            } else {
+                selector.replace(":scope", ":host")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Splits at the separator, except where it is inside of a quoted string, parentheses or brackets
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    for (i, c) in top_level_chars(s).filter(|(_, c)| *c == separator) {
+        parts.push(&s[start..i]);
+        start = i + c.len_utf8();
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Index of the brace closing the one at `open`, or the end of the string if it is never closed
+fn matching_brace(s: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (i, c) in top_level_chars(&s[open..]) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + i;
+                }
+            }
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+fn find_top_level(s: &str, needle: char) -> Option<usize> {
+    top_level_chars(s)
+        .find(|(_, c)| *c == needle)
+        .map(|(i, _)| i)
+}
+
+/// The characters outside of quoted strings, parentheses and brackets, with their index.
+/// Only at this level, braces, commas and semicolons structure the style-sheet.
+fn top_level_chars(s: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut depth = 0_usize;
+    s.char_indices().filter(move |(_, c)| {
+        let c = *c;
+        if escaped {
+            escaped = false;
+            return false;
+        }
+        match quote {
+            _ if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '(' || c == '[' => depth += 1,
+            None if c == ')' || c == ']' => depth = depth.saturating_sub(1),
+            None => return depth == 0,
+        }
+        false
+    })
+}
+
+/// Removes comments, except where the comment markers are inside of a quoted string
+fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut quote = None;
+    let mut escaped = false;
+    let mut i = 0;
+    while let Some(c) = css[i..].chars().next() {
+        if quote.is_none() && !escaped && css[i..].starts_with("/*") {
+            i = match css[i + 2..].find("*/") {
+                Some(end) => i + 2 + end + 2,
+                None => css.len(),
+            };
+            continue;
+        }
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if quote == Some(c) {
+            quote = None;
+        } else if quote.is_none() && (c == '"' || c == '\'') {
+            quote = Some(c);
+        }
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_every_selector() {
+        assert_eq!(
+            scope_css(".a, .b > p { color: red; }", ".s"),
+            ".s .a, .s .b > p { color: red; }\n"
+        );
+        assert_eq!(
+            scope_css("&.active, & > p, :scope:hover { color: red; }", ".s"),
+            ".s.active, .s > p, .s:hover { color: red; }\n"
+        );
+    }
+
+    #[test]
+    fn scopes_rules_in_nested_grouping_rules() {
+        let css = "@media (max-width: 600px) { .a { color: red; } @supports (display: grid) { p, :scope { display: grid; } } }";
+        assert_eq!(
+            scope_css(css, ".s"),
+            "@media (max-width: 600px) {\n.s .a { color: red; }\n@supports (display: grid) {\n.s p, .s { display: grid; }\n}\n}\n"
+        );
+    }
+
+    #[test]
+    fn keeps_keyframes_and_statements() {
+        let css = "@import url(\"x.css\"); @keyframes spin { from { transform: rotate(0deg); } to { transform: rotate(360deg); } } .a { animation: spin 1s; }";
+        assert_eq!(
+            scope_css(css, ".s"),
+            "@import url(\"x.css\");\n@keyframes spin { from { transform: rotate(0deg); } to { transform: rotate(360deg); } }\n.s .a { animation: spin 1s; }\n"
+        );
+    }
+
+    #[test]
+    fn removes_comments() {
+        assert_eq!(
+            scope_css(
+                "/* .x { color: red } */ .a { /* } */ color: red; } /* trailing",
+                ".s"
+            ),
+            ".s .a {  color: red; }\n"
+        );
+    }
+
+    #[test]
+    fn ignores_braces_and_commas_in_strings_and_urls() {
+        let css =
+            ".a::after { content: \"}\"; } .b[title='a,b{'] { content: '/* not a comment */'; }";
+        assert_eq!(
+            scope_css(css, ".s"),
+            ".s .a::after { content: \"}\"; }\n.s .b[title='a,b{'] { content: '/* not a comment */'; }\n"
+        );
+        let css =
+            ".a { background: url(data:image/svg+xml;utf8,<svg>}</svg>); } .b { color: red; }";
+        assert_eq!(
+            scope_css(css, ".s"),
+            ".s .a { background: url(data:image/svg+xml;utf8,<svg>}</svg>); }\n.s .b { color: red; }\n"
+        );
+    }
+
+    #[test]
+    fn host_replaces_only_the_scope() {
+        assert_eq!(
+            host_css("&.active, & > p, :scope:hover, .a { color: red; }"),
+            ":host(.active), :host > p, :host:hover, .a { color: red; }\n"
+        );
+        assert_eq!(
+            host_css("@media print { :scope { display: none; } }"),
+            "@media print {\n:host { display: none; }\n}\n"
+        );
+    }
+}